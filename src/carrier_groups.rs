@@ -0,0 +1,219 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::data::{CarrierGroup, FlightData, ServiceClass};
+use crate::graph::{Breakdowns, Graph};
+use crate::timeline::{Granularity, Period, PeriodGrid};
+
+/// Separate airport graphs for the flights of US and of foreign carriers.
+#[derive(Debug, Default)]
+pub struct GroupGraphs {
+    pub us: Graph,
+    pub foreign: Graph,
+}
+
+impl GroupGraphs {
+    pub fn get(&self, group: CarrierGroup) -> &Graph {
+        match group {
+            CarrierGroup::Us => &self.us,
+            CarrierGroup::Foreign => &self.foreign,
+        }
+    }
+}
+
+/// Builds one graph per carrier group from the flights of one class of
+/// service.
+pub fn build_group_graphs(flights: &[FlightData], class: ServiceClass) -> GroupGraphs {
+    let mut graphs = GroupGraphs::default();
+
+    for flight in flights {
+        let graph = match flight.carrier_group {
+            CarrierGroup::Us => &mut graphs.us,
+            CarrierGroup::Foreign => &mut graphs.foreign,
+        };
+        graph.add_flight(flight, class, Breakdowns::default());
+    }
+
+    graphs
+}
+
+/// Which carrier groups fly a route.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RouteService {
+    UsOnly,
+    ForeignOnly,
+    Both,
+}
+
+/// Flights on one route by US and by foreign carriers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteSplit {
+    /// Endpoints in code order, as routes are undirected.
+    pub route: (String, String),
+    pub us: u64,
+    pub foreign: u64,
+}
+
+impl RouteSplit {
+    pub fn service(&self) -> RouteService {
+        match (self.us > 0, self.foreign > 0) {
+            (true, false) => RouteService::UsOnly,
+            (false, true) => RouteService::ForeignOnly,
+            _ => RouteService::Both,
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.us + self.foreign
+    }
+
+    /// Fraction of the route's flights operated by US carriers.
+    pub fn us_share(&self) -> f64 {
+        self.us as f64 / self.total() as f64
+    }
+}
+
+/// Every route of either group with its flights split by group, busiest
+/// first and ties broken by route.
+pub fn route_splits(graphs: &GroupGraphs) -> Vec<RouteSplit> {
+    let mut splits: BTreeMap<(String, String), RouteSplit> = BTreeMap::new();
+
+    for group in [CarrierGroup::Us, CarrierGroup::Foreign] {
+        // Undirected routes come with their endpoints in code order
+        for (route, weight) in graphs.get(group).routes() {
+            let split = splits.entry(route.clone()).or_insert(RouteSplit {
+                route,
+                us: 0,
+                foreign: 0,
+            });
+            match group {
+                CarrierGroup::Us => split.us += weight,
+                CarrierGroup::Foreign => split.foreign += weight,
+            }
+        }
+    }
+
+    let mut splits: Vec<RouteSplit> = splits.into_values().collect();
+    splits.sort_by(|a, b| {
+        b.total()
+            .cmp(&a.total())
+            .then_with(|| a.route.cmp(&b.route))
+    });
+    splits
+}
+
+/// Flights at an airport in one period by US and by foreign carriers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GroupShare {
+    pub period: Period,
+    pub us: u64,
+    pub foreign: u64,
+}
+
+impl GroupShare {
+    /// Fraction of the flights operated by US carriers, `None` for a period
+    /// without flights.
+    pub fn us_share(&self) -> Option<f64> {
+        let total = self.us + self.foreign;
+        (total > 0).then(|| self.us as f64 / total as f64)
+    }
+}
+
+/// Each airport's flights by carrier group in every period covered by
+/// `flights`, counting both ends of a route. Periods without flights are
+/// kept with zero counts so the series line up.
+pub fn airport_group_shares(
+    flights: &[FlightData],
+    class: ServiceClass,
+    granularity: Granularity,
+) -> BTreeMap<String, Vec<GroupShare>> {
+    let Some(grid) = PeriodGrid::covering(flights, granularity) else {
+        return BTreeMap::new();
+    };
+    let empty: Vec<GroupShare> = grid
+        .periods()
+        .map(|period| GroupShare {
+            period,
+            us: 0,
+            foreign: 0,
+        })
+        .collect();
+
+    let mut shares: HashMap<&str, Vec<GroupShare>> = HashMap::new();
+    for flight in flights {
+        let count = flight.flights(class) as u64;
+        if count == 0 {
+            continue;
+        }
+        let index = grid.index_of(flight.date).unwrap();
+        for airport in [&flight.us_airport, &flight.foreign_airport] {
+            let share = &mut shares.entry(airport).or_insert_with(|| empty.clone())[index];
+            match flight.carrier_group {
+                CarrierGroup::Us => share.us += count,
+                CarrierGroup::Foreign => share.foreign += count,
+            }
+        }
+    }
+
+    shares
+        .into_iter()
+        .map(|(airport, series)| (airport.to_string(), series))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flight(year: u32, us: &str, foreign: &str, group: CarrierGroup, flights: u32) -> FlightData {
+        FlightData {
+            carrier_group: group,
+            ..FlightData::for_test(year, 1, us, foreign, "XX", flights, 0)
+        }
+    }
+
+    fn sample() -> Vec<FlightData> {
+        vec![
+            flight(2019, "JFK", "LHR", CarrierGroup::Us, 30),
+            flight(2019, "JFK", "LHR", CarrierGroup::Foreign, 10),
+            flight(2019, "MIA", "BOG", CarrierGroup::Foreign, 8),
+            flight(2021, "JFK", "CDG", CarrierGroup::Us, 5),
+        ]
+    }
+
+    #[test]
+    fn test_route_splits() {
+        let graphs = build_group_graphs(&sample(), ServiceClass::Total);
+        assert_eq!(graphs.us.edge_count(), 2);
+        assert_eq!(graphs.get(CarrierGroup::Foreign).node_count(), 4);
+
+        let splits = route_splits(&graphs);
+        let services: Vec<_> = splits
+            .iter()
+            .map(|s| (s.route.0.as_str(), s.service()))
+            .collect();
+        assert_eq!(
+            services,
+            [
+                ("JFK", RouteService::Both),
+                ("BOG", RouteService::ForeignOnly),
+                ("CDG", RouteService::UsOnly),
+            ]
+        );
+        assert_eq!(splits[0].us_share(), 0.75);
+    }
+
+    #[test]
+    fn test_airport_group_shares() {
+        let shares = airport_group_shares(&sample(), ServiceClass::Total, Granularity::Year);
+
+        let jfk = &shares["JFK"];
+        assert_eq!(jfk.len(), 3);
+        assert_eq!(
+            (jfk[0].us, jfk[0].foreign, jfk[0].us_share()),
+            (30, 10, Some(0.75))
+        );
+        assert_eq!(jfk[1].us_share(), None); // 2020 had no flights
+        assert_eq!(jfk[2].period.to_string(), "2021");
+        assert_eq!(shares["BOG"][0].us_share(), Some(0.0));
+    }
+}
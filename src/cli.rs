@@ -0,0 +1,1574 @@
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::Write;
+use std::ops::RangeInclusive;
+
+use crate::carrier_groups::{
+    airport_group_shares, build_group_graphs, route_splits, RouteService, RouteSplit,
+};
+use crate::compare::{
+    compare_periods, largest_decreases, largest_increases, largest_percent_changes, Change,
+};
+use crate::data::{read_csv_with, CarrierGroup, FlightData, ParseMode, ReadOptions, ServiceClass};
+use crate::filter::RecordFilter;
+use crate::graph::{
+    airport_totals, build_directed_graph, build_graph, build_multiplex, BetweennessOptions,
+    DistanceCentrality, EdgeCost, EigenvectorOptions, Graph, IterativeScores, LayerBy,
+    LouvainOptions, PageRankOptions,
+};
+use crate::hubs::{carrier_profiles, HubOptions, NetworkProfile, NetworkShape};
+use crate::market::{airport_markets, gateway_markets, route_markets, Market};
+use crate::output::{write_tables, OutputFormat, Table, Value};
+use crate::ranking::top_k;
+use crate::timeline::{
+    airport_series, carrier_series, metric_series, route_series, Granularity, Period,
+    SnapshotOptions,
+};
+
+pub const DEFAULT_INPUT: &str = "International_Report_Departures.csv";
+
+pub const USAGE: &str = "\
+Usage: Final_Project [OPTIONS] [COMMAND] [ARGS]
+
+Commands:
+  report          Stats, busiest airports, components and centrality (default)
+  stats           Record, node, edge and component counts
+  top-airports    US gateways ranked by flights
+  components      Connected components by size
+  centrality      Airports ranked by a centrality measure
+  path FROM TO    Fewest-legs itinerary between two airports
+  export          Weighted edge list of the airport graph
+  timeline        Graph metrics and top airport for each period
+  series KIND     Monthly flights per airport, route or carrier, zero-filled
+  compare P1 P2   Route and airport changes between two periods, e.g.
+                  2019 2020 or 2019-01..2019-03 2020-01..2020-03
+  layers          Per-carrier network layers, their route overlap and
+                  airports' multiplex participation
+  groups          US vs foreign carrier networks: route service and each
+                  group's share at the busiest airports per period
+  hubs            Each carrier's hubs, concentration and hub-and-spoke or
+                  point-to-point shape
+  communities     Louvain communities of the flight-weighted graph
+  cuts            Airports and routes whose loss cuts off part of the
+                  network, with how many airports they cut off
+  market KIND     Carrier shares and HHI per airport, gateway (US airports
+                  only) or route, most concentrated first
+
+Options:
+  -i, --input PATH     CSV extract to read; repeat to combine several
+                       (default: International_Report_Departures.csv)
+  -n, --top N          Number of rows to show (default: 5)
+      --year Y[-Y]     Only use records from these years
+      --month M[-M]    Only use records from these months
+      --carrier LIST   Only use these carriers (comma-separated, repeatable)
+      --exclude-carrier LIST
+                       Leave out these carriers
+      --us-airport LIST
+                       Only use departures from these US gateways
+      --foreign-airport LIST
+                       Only use flights to these foreign airports
+      --carrier-group GROUP
+                       us or foreign carriers only
+      --min-flights N  Skip records with fewer flights of the chosen class
+      --class CLASS    scheduled, charter or total (default: total)
+      --format FORMAT  text, csv or json (default: text)
+      --directed       Keep the direction of travel: departures go US to
+                       foreign, arrivals foreign to US
+      --lenient        Skip bad rows and report them instead of stopping at
+                       the first one
+      --measure NAME   centrality: harmonic, normalized-harmonic, closeness
+                       (undirected only), wasserman-faust, betweenness,
+                       pagerank or eigenvector
+      --largest-component
+                       centrality: only rank airports in the largest component
+      --cost COST      path, harmonic centrality: hops, inverse-flights or
+                       neg-log-share (path only)
+      --all            path: list up to --top equally short itineraries
+                       (hops only)
+  -o, --output PATH    export: write to a file instead of stdout
+      --period PERIOD  timeline, groups: year, month or a number of months
+                       (default: year)
+      --layer-by KIND  layers: carrier or group (default: carrier)
+      --resolution R   communities: above 1 for smaller communities, below 1
+                       for larger ones (default: 1)
+      --core-coverage SHARE
+                       hubs: share of flights a network's busiest airports
+                       must touch to form its core (default: 0.8)
+      --max-core FRACTION
+                       hubs: largest fraction of airports in the core of a
+                       hub-and-spoke network (default: 0.2)
+      --min-base N     compare: flights needed in the first period to be
+                       ranked by percent change (default: 100)
+  -h, --help           Show this help
+";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Report,
+    Stats,
+    TopAirports,
+    Components,
+    Centrality,
+    Path { from: String, to: String },
+    Export,
+    Timeline,
+    Series(SeriesKind),
+    Compare { before: Period, after: Period },
+    Market(MarketKind),
+    Layers,
+    Groups,
+    Hubs,
+    Communities,
+    Cuts,
+    Help,
+}
+
+/// What the `market` command computes carrier shares for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarketKind {
+    Airport,
+    Gateway,
+    Route,
+}
+
+/// What the `series` command keys its monthly totals by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeriesKind {
+    Airport,
+    Route,
+    Carrier,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CentralityMeasure {
+    Distance(DistanceCentrality),
+    Betweenness,
+    PageRank,
+    Eigenvector,
+}
+
+/// Parsed command line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cli {
+    pub command: Command,
+    pub inputs: Vec<String>,
+    pub top: usize,
+    pub filter: RecordFilter,
+    pub class: ServiceClass,
+    pub format: OutputFormat,
+    pub directed: bool,
+    pub mode: ParseMode,
+    pub measure: CentralityMeasure,
+    pub largest_component: bool,
+    pub cost: EdgeCost,
+    pub all_paths: bool,
+    pub output: Option<String>,
+    pub period: Granularity,
+    pub min_base: u64,
+    pub layer_by: LayerBy,
+    pub resolution: f64,
+    pub core_coverage: f64,
+    pub max_core_fraction: f64,
+}
+
+impl Default for Cli {
+    fn default() -> Self {
+        Self {
+            command: Command::Report,
+            inputs: Vec::new(),
+            top: 5,
+            filter: RecordFilter::new(),
+            class: ServiceClass::Total,
+            format: OutputFormat::Text,
+            directed: false,
+            mode: ParseMode::Strict,
+            measure: CentralityMeasure::Distance(DistanceCentrality::Harmonic),
+            largest_component: false,
+            cost: EdgeCost::Hops,
+            all_paths: false,
+            output: None,
+            period: Granularity::Year,
+            min_base: 100,
+            layer_by: LayerBy::Carrier,
+            resolution: 1.0,
+            core_coverage: HubOptions::default().core_coverage,
+            max_core_fraction: HubOptions::default().max_core_fraction,
+        }
+    }
+}
+
+/// Invalid command-line arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliError(pub String);
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Error for CliError {}
+
+impl Cli {
+    /// Parses arguments, not including the program name.
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, CliError> {
+        let mut cli = Cli::default();
+        let mut positional = Vec::new();
+        let mut min_flights = None;
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .ok_or_else(|| CliError(format!("{} needs a value", name)))
+            };
+            match arg.as_str() {
+                "-h" | "--help" => cli.command = Command::Help,
+                "-i" | "--input" => cli.inputs.push(value(&arg)?),
+                "-n" | "--top" => cli.top = parse_number(&arg, &value(&arg)?)? as usize,
+                "--year" => cli.filter.years = Some(parse_range(&arg, &value(&arg)?)?),
+                "--month" => cli.filter.months = Some(parse_range(&arg, &value(&arg)?)?),
+                "--carrier" => cli.filter = cli.filter.carriers(parse_list(&value(&arg)?)),
+                "--exclude-carrier" => {
+                    cli.filter = cli.filter.exclude_carriers(parse_list(&value(&arg)?))
+                }
+                "--us-airport" => cli.filter = cli.filter.us_airports(parse_list(&value(&arg)?)),
+                "--foreign-airport" => {
+                    cli.filter = cli.filter.foreign_airports(parse_list(&value(&arg)?))
+                }
+                "--carrier-group" => {
+                    cli.filter.carrier_group = Some(parse_carrier_group(&value(&arg)?)?)
+                }
+                "--min-flights" => min_flights = Some(parse_number(&arg, &value(&arg)?)?),
+                "--class" => cli.class = parse_class(&value(&arg)?)?,
+                "--format" => cli.format = value(&arg)?.parse().map_err(CliError)?,
+                "--directed" => cli.directed = true,
+                "--lenient" => cli.mode = ParseMode::Lenient,
+                "--measure" => cli.measure = parse_measure(&value(&arg)?)?,
+                "--largest-component" => cli.largest_component = true,
+                "--cost" => cli.cost = parse_cost(&value(&arg)?)?,
+                "--all" => cli.all_paths = true,
+                "-o" | "--output" => cli.output = Some(value(&arg)?),
+                "--period" => cli.period = parse_period(&value(&arg)?)?,
+                "--resolution" => cli.resolution = parse_positive(&arg, &value(&arg)?)?,
+                "--core-coverage" => cli.core_coverage = parse_share(&arg, &value(&arg)?)?,
+                "--max-core" => cli.max_core_fraction = parse_share(&arg, &value(&arg)?)?,
+                "--layer-by" => cli.layer_by = parse_layer_by(&value(&arg)?)?,
+                "--min-base" => cli.min_base = parse_number(&arg, &value(&arg)?)? as u64,
+                _ if arg.starts_with('-') => {
+                    return Err(CliError(format!("unknown option '{}'", arg)))
+                }
+                _ => positional.push(arg),
+            }
+        }
+
+        if cli.command == Command::Help {
+            return Ok(cli);
+        }
+        if cli.inputs.is_empty() {
+            cli.inputs.push(DEFAULT_INPUT.to_string());
+        }
+        if let Some(min) = min_flights {
+            // Counted in whichever --class was given, wherever it appeared
+            cli.filter.min_flights = Some((cli.class, min));
+        }
+        if let Some(months) = &cli.filter.months {
+            if *months.start() < 1 || *months.end() > 12 {
+                return Err(CliError("--month must be between 1 and 12".to_string()));
+            }
+        }
+
+        let mut positional = positional.into_iter();
+        cli.command = match positional.next().as_deref() {
+            None | Some("report") => Command::Report,
+            Some("stats") => Command::Stats,
+            Some("top-airports") => Command::TopAirports,
+            Some("components") => Command::Components,
+            Some("centrality") => Command::Centrality,
+            Some("export") => Command::Export,
+            Some("timeline") => Command::Timeline,
+            Some("compare") => match (positional.next(), positional.next()) {
+                (Some(before), Some(after)) => Command::Compare {
+                    before: parse_compared(&before)?,
+                    after: parse_compared(&after)?,
+                },
+                _ => return Err(CliError("compare needs two periods".to_string())),
+            },
+            Some("layers") => Command::Layers,
+            Some("groups") => Command::Groups,
+            Some("hubs") => Command::Hubs,
+            Some("communities") => Command::Communities,
+            Some("cuts") => Command::Cuts,
+            Some("market") => match positional.next().as_deref() {
+                Some("airport") => Command::Market(MarketKind::Airport),
+                Some("gateway") => Command::Market(MarketKind::Gateway),
+                Some("route") => Command::Market(MarketKind::Route),
+                _ => {
+                    return Err(CliError(
+                        "market needs airport, gateway or route".to_string(),
+                    ))
+                }
+            },
+            Some("series") => match positional.next().as_deref() {
+                Some("airport") => Command::Series(SeriesKind::Airport),
+                Some("route") => Command::Series(SeriesKind::Route),
+                Some("carrier") => Command::Series(SeriesKind::Carrier),
+                _ => {
+                    return Err(CliError(
+                        "series needs airport, route or carrier".to_string(),
+                    ))
+                }
+            },
+            Some("path") => match (positional.next(), positional.next()) {
+                (Some(from), Some(to)) => Command::Path { from, to },
+                _ => return Err(CliError("path needs FROM and TO airports".to_string())),
+            },
+            Some(other) => return Err(CliError(format!("unknown command '{}'", other))),
+        };
+        if let Some(extra) = positional.next() {
+            return Err(CliError(format!("unexpected argument '{}'", extra)));
+        }
+        if cli.all_paths && cli.cost != EdgeCost::Hops {
+            return Err(CliError("--all only works with --cost hops".to_string()));
+        }
+        if cli.cost != EdgeCost::Hops
+            && cli.measure != CentralityMeasure::Distance(DistanceCentrality::Harmonic)
+        {
+            return Err(CliError(
+                "--cost only works with --measure harmonic".to_string(),
+            ));
+        }
+        if cli.directed && cli.measure == CentralityMeasure::Distance(DistanceCentrality::Closeness)
+        {
+            return Err(CliError(
+                "closeness is not comparable across airports with --directed, since most \
+                 reach only a few others; use --measure wasserman-faust"
+                    .to_string(),
+            ));
+        }
+
+        Ok(cli)
+    }
+}
+
+fn parse_number(name: &str, value: &str) -> Result<u32, CliError> {
+    value
+        .parse()
+        .map_err(|_| CliError(format!("{} expects a number, got '{}'", name, value)))
+}
+
+fn parse_positive(name: &str, value: &str) -> Result<f64, CliError> {
+    match value.parse::<f64>() {
+        Ok(x) if x > 0.0 && x.is_finite() => Ok(x),
+        _ => Err(CliError(format!(
+            "{} expects a positive number, got '{}'",
+            name, value
+        ))),
+    }
+}
+
+fn parse_share(name: &str, value: &str) -> Result<f64, CliError> {
+    match parse_positive(name, value)? {
+        x if x <= 1.0 => Ok(x),
+        _ => Err(CliError(format!(
+            "{} expects a share between 0 and 1, got '{}'",
+            name, value
+        ))),
+    }
+}
+
+/// Parses `N` or `N-M` into an inclusive range.
+fn parse_range(name: &str, value: &str) -> Result<RangeInclusive<u32>, CliError> {
+    let (start, end) = value.split_once('-').unwrap_or((value, value));
+    let (start, end) = (parse_number(name, start)?, parse_number(name, end)?);
+    if start > end {
+        return Err(CliError(format!("{} range '{}' is empty", name, value)));
+    }
+    Ok(start..=end)
+}
+
+fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|code| !code.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn parse_carrier_group(value: &str) -> Result<CarrierGroup, CliError> {
+    match value {
+        "us" => Ok(CarrierGroup::Us),
+        "foreign" => Ok(CarrierGroup::Foreign),
+        _ => Err(CliError(format!("unknown carrier group '{}'", value))),
+    }
+}
+
+fn parse_period(value: &str) -> Result<Granularity, CliError> {
+    match value {
+        "year" => Ok(Granularity::Year),
+        "month" => Ok(Granularity::Month),
+        _ => match value.parse() {
+            Ok(months) if months > 0 => Ok(Granularity::Months(months)),
+            _ => Err(CliError(format!("unknown period '{}'", value))),
+        },
+    }
+}
+
+fn parse_compared(value: &str) -> Result<Period, CliError> {
+    value
+        .parse()
+        .map_err(|_| CliError(format!("invalid period '{}'", value)))
+}
+
+fn parse_layer_by(value: &str) -> Result<LayerBy, CliError> {
+    match value {
+        "carrier" => Ok(LayerBy::Carrier),
+        "group" => Ok(LayerBy::CarrierGroup),
+        _ => Err(CliError(format!("unknown layering '{}'", value))),
+    }
+}
+
+fn parse_class(value: &str) -> Result<ServiceClass, CliError> {
+    match value {
+        "scheduled" => Ok(ServiceClass::Scheduled),
+        "charter" => Ok(ServiceClass::Charter),
+        "total" => Ok(ServiceClass::Total),
+        _ => Err(CliError(format!("unknown service class '{}'", value))),
+    }
+}
+
+fn parse_measure(value: &str) -> Result<CentralityMeasure, CliError> {
+    match value {
+        "harmonic" => Ok(CentralityMeasure::Distance(DistanceCentrality::Harmonic)),
+        "normalized-harmonic" => Ok(CentralityMeasure::Distance(
+            DistanceCentrality::NormalizedHarmonic,
+        )),
+        "closeness" => Ok(CentralityMeasure::Distance(DistanceCentrality::Closeness)),
+        "wasserman-faust" => Ok(CentralityMeasure::Distance(
+            DistanceCentrality::WassermanFaust,
+        )),
+        "betweenness" => Ok(CentralityMeasure::Betweenness),
+        "pagerank" => Ok(CentralityMeasure::PageRank),
+        "eigenvector" => Ok(CentralityMeasure::Eigenvector),
+        _ => Err(CliError(format!("unknown centrality measure '{}'", value))),
+    }
+}
+
+fn parse_cost(value: &str) -> Result<EdgeCost, CliError> {
+    match value {
+        "hops" => Ok(EdgeCost::Hops),
+        "inverse-flights" => Ok(EdgeCost::InverseFlights),
+        "neg-log-share" => Ok(EdgeCost::NegLogShare),
+        _ => Err(CliError(format!("unknown path cost '{}'", value))),
+    }
+}
+
+/// Loads the inputs and runs the selected command, writing to `out`.
+pub fn run(cli: &Cli, out: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+    if cli.command == Command::Help {
+        write!(out, "{}", USAGE)?;
+        return Ok(());
+    }
+
+    let flights = load_flights(cli)?;
+    let graph = if cli.directed {
+        build_directed_graph(&flights, cli.class)
+    } else {
+        build_graph(&flights, cli.class)
+    };
+
+    let tables = match &cli.command {
+        Command::Report => vec![
+            stats_table(&flights, &graph),
+            top_airports_table(cli, &flights),
+            components_table(cli, &graph),
+            centrality_table(
+                &Cli {
+                    largest_component: true,
+                    ..cli.clone()
+                },
+                &graph,
+            )?,
+        ],
+        Command::Stats => vec![stats_table(&flights, &graph)],
+        Command::TopAirports => vec![top_airports_table(cli, &flights)],
+        Command::Components => vec![components_table(cli, &graph)],
+        Command::Centrality => vec![centrality_table(cli, &graph)?],
+        Command::Path { from, to } => vec![path_table(cli, &graph, from, to)?],
+        Command::Export => {
+            let table = edges_table(&graph);
+            if let Some(path) = &cli.output {
+                table.write(&mut File::create(path)?, cli.format)?;
+                return Ok(());
+            }
+            vec![table]
+        }
+        Command::Timeline => vec![timeline_table(cli, &flights)],
+        Command::Series(kind) => vec![series_table(cli, *kind, &flights)],
+        Command::Compare { before, after } => compare_tables(cli, &flights, *before, *after),
+        Command::Market(kind) => vec![market_table(cli, *kind, &flights)],
+        Command::Layers => layer_tables(cli, &flights),
+        Command::Groups => group_tables(cli, &flights),
+        Command::Hubs => hub_tables(cli, &flights),
+        Command::Communities => vec![communities_table(cli, &graph)],
+        Command::Cuts => cut_tables(cli, &graph),
+        Command::Help => unreachable!(),
+    };
+
+    write_tables(&tables, out, cli.format)
+}
+
+fn load_flights(cli: &Cli) -> Result<Vec<FlightData>, Box<dyn Error>> {
+    let options = ReadOptions {
+        mode: cli.mode,
+        filter: cli.filter.clone(),
+        ..ReadOptions::default()
+    };
+    let mut flights = Vec::new();
+
+    for input in &cli.inputs {
+        let ingest = read_csv_with(input, &options)?;
+        if cli.mode == ParseMode::Lenient {
+            // Keep diagnostics off stdout, which may be piped as CSV/JSON
+            eprintln!("{}: {}", input, ingest.report);
+        }
+        flights.extend(ingest.flights);
+    }
+
+    Ok(flights)
+}
+
+fn stats_table(flights: &[FlightData], graph: &Graph) -> Table {
+    let components = graph.component_ids();
+    let largest = components.iter().map(Vec::len).max().unwrap_or(0);
+
+    let mut table = Table::new("Graph Statistics", &["metric", "value"]);
+    table.push(vec!["records".into(), flights.len().into()]);
+    table.push(vec!["nodes".into(), graph.node_count().into()]);
+    table.push(vec!["edges".into(), graph.edge_count().into()]);
+    table.push(vec!["components".into(), components.len().into()]);
+    table.push(vec!["largest component".into(), largest.into()]);
+    table
+}
+
+fn top_airports_table(cli: &Cli, flights: &[FlightData]) -> Table {
+    let title = format!("Top {} Busiest Airports", cli.top);
+    let mut table = Table::new(&title, &["airport", "flights"]);
+    for (airport, total) in top_k(&airport_totals(flights, cli.class), cli.top, |_| true) {
+        table.push(vec![airport.into(), total.into()]);
+    }
+    table
+}
+
+fn components_table(cli: &Cli, graph: &Graph) -> Table {
+    let mut sizes: Vec<usize> = graph.component_ids().iter().map(Vec::len).collect();
+    sizes.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut table = Table::new("Connected Components", &["component", "nodes"]);
+    for (i, size) in sizes.into_iter().take(cli.top).enumerate() {
+        table.push(vec![(i + 1).into(), size.into()]);
+    }
+    table
+}
+
+fn centrality_scores(measure: CentralityMeasure, graph: &Graph) -> HashMap<String, f64> {
+    match measure {
+        CentralityMeasure::Distance(measure) => graph.centrality(measure),
+        CentralityMeasure::Betweenness => {
+            graph.betweenness_centrality(&BetweennessOptions::default())
+        }
+        CentralityMeasure::PageRank => {
+            let result = graph.pagerank_scores(&PageRankOptions::default());
+            warn_unconverged("PageRank", &result);
+            graph.scores_by_code(result.scores)
+        }
+        CentralityMeasure::Eigenvector => {
+            let result = graph.eigenvector_scores(&EigenvectorOptions::default());
+            warn_unconverged("eigenvector centrality", &result);
+            graph.scores_by_code(result.scores)
+        }
+    }
+}
+
+fn warn_unconverged(measure: &str, result: &IterativeScores) {
+    if !result.converged {
+        eprintln!(
+            "warning: {} did not converge after {} iterations; scores are approximate",
+            measure, result.iterations
+        );
+    }
+}
+
+fn centrality_table(cli: &Cli, graph: &Graph) -> Result<Table, CliError> {
+    let scores = if cli.cost == EdgeCost::Hops {
+        centrality_scores(cli.measure, graph)
+    } else {
+        graph
+            .weighted_harmonic_centrality(cli.cost)
+            .map_err(|err| CliError(err.to_string()))?
+    };
+
+    let (ranked, scope) = if cli.largest_component {
+        let largest = graph.largest_component();
+        let ranked = top_k(&scores, cli.top, |code| largest.contains(code));
+        (ranked, " (Largest Component)")
+    } else {
+        (top_k(&scores, cli.top, |_| true), "")
+    };
+
+    let title = format!("Top {} Airports by Centrality{}", cli.top, scope);
+    let mut table = Table::new(&title, &["airport", "score"]);
+    for (airport, score) in ranked {
+        table.push(vec![airport.into(), score.into()]);
+    }
+    Ok(table)
+}
+
+fn path_table(cli: &Cli, graph: &Graph, from: &str, to: &str) -> Result<Table, CliError> {
+    for code in [from, to] {
+        if graph.node_id(code).is_none() {
+            return Err(CliError(format!("unknown airport '{}'", code)));
+        }
+    }
+
+    let paths = if cli.cost != EdgeCost::Hops {
+        graph
+            .weighted_path(from, to, cli.cost)
+            .into_iter()
+            .collect()
+    } else if cli.all_paths {
+        graph.all_shortest_paths(from, to, cli.top)
+    } else {
+        graph.shortest_path(from, to).into_iter().collect()
+    };
+
+    let title = format!("Itineraries from {} to {}", from, to);
+    let mut table = Table::new(&title, &["legs", "itinerary"]);
+    for path in paths {
+        table.push(vec![(path.len() - 1).into(), path.join(" > ").into()]);
+    }
+    Ok(table)
+}
+
+fn edges_table(graph: &Graph) -> Table {
+    let mut table = Table::new("Edges", &["source", "target", "flights"]);
+    for ((source, target), flights) in graph.routes() {
+        table.push(vec![source.into(), target.into(), flights.into()]);
+    }
+    table
+}
+
+fn timeline_table(cli: &Cli, flights: &[FlightData]) -> Table {
+    let options = SnapshotOptions {
+        granularity: cli.period,
+        class: cli.class,
+        directed: cli.directed,
+    };
+    let series = metric_series(flights, &options, 1, |graph| {
+        centrality_scores(cli.measure, graph)
+    });
+
+    let mut table = Table::new(
+        "Network by Period",
+        &[
+            "period",
+            "records",
+            "nodes",
+            "edges",
+            "flights",
+            "components",
+            "largest component",
+            "top airport",
+            "score",
+        ],
+    );
+    for metrics in series {
+        let (top, score) = match metrics.top.into_iter().next() {
+            Some((airport, score)) => (airport.into(), score.into()),
+            None => ("".into(), "".into()),
+        };
+        table.push(vec![
+            metrics.period.to_string().into(),
+            metrics.records.into(),
+            metrics.nodes.into(),
+            metrics.edges.into(),
+            metrics.flights.into(),
+            metrics.components.into(),
+            metrics.largest_component.into(),
+            top,
+            score,
+        ]);
+    }
+    table
+}
+
+fn series_table(cli: &Cli, kind: SeriesKind, flights: &[FlightData]) -> Table {
+    let title = "Monthly Flights";
+    match kind {
+        SeriesKind::Route => {
+            let series = route_series(flights, cli.class);
+            let mut table = Table::new(
+                title,
+                &["us airport", "foreign airport", "month", "flights"],
+            );
+            for ((us, foreign), values) in &series.series {
+                for (month, flights) in series.months().zip(values) {
+                    table.push(vec![
+                        us.as_str().into(),
+                        foreign.as_str().into(),
+                        month.to_string().into(),
+                        (*flights).into(),
+                    ]);
+                }
+            }
+            table
+        }
+        SeriesKind::Airport | SeriesKind::Carrier => {
+            let (series, key) = if kind == SeriesKind::Airport {
+                (airport_series(flights, cli.class), "airport")
+            } else {
+                (carrier_series(flights, cli.class), "carrier")
+            };
+            let mut table = Table::new(title, &[key, "month", "flights"]);
+            for (code, values) in &series.series {
+                for (month, flights) in series.months().zip(values) {
+                    table.push(vec![
+                        code.as_str().into(),
+                        month.to_string().into(),
+                        (*flights).into(),
+                    ]);
+                }
+            }
+            table
+        }
+    }
+}
+
+fn change_row(mut row: Vec<Value>, change: &Change<impl Sized>) -> Vec<Value> {
+    row.push(change.before.into());
+    row.push(change.after.into());
+    row.push(change.delta().into());
+    row.push(change.percent().map_or_else(|| "".into(), Value::from));
+    row
+}
+
+fn compare_tables(cli: &Cli, flights: &[FlightData], before: Period, after: Period) -> Vec<Table> {
+    let comparison = compare_periods(flights, cli.class, before, after);
+    let span = format!("{} to {}", before, after);
+    let route_headers = ["source", "target", "before", "after", "change", "percent"];
+    let airport_headers = ["airport", "before", "after", "change", "percent"];
+
+    let route_table = |title: &str, changes: Vec<Change<(String, String)>>| {
+        let mut table = Table::new(&format!("{}, {}", title, span), &route_headers);
+        for change in changes.iter().take(cli.top) {
+            let (source, target) = &change.key;
+            table.push(change_row(
+                vec![source.as_str().into(), target.as_str().into()],
+                change,
+            ));
+        }
+        table
+    };
+    let airport_table = |title: &str, changes: Vec<Change<String>>| {
+        let mut table = Table::new(&format!("{}, {}", title, span), &airport_headers);
+        for change in &changes {
+            table.push(change_row(vec![change.key.as_str().into()], change));
+        }
+        table
+    };
+
+    let (routes, airports) = (&comparison.routes, &comparison.airports);
+    vec![
+        route_table(
+            "Largest Route Increases",
+            largest_increases(routes, cli.top),
+        ),
+        route_table(
+            "Largest Route Decreases",
+            largest_decreases(routes, cli.top),
+        ),
+        route_table(
+            "Largest Route Percent Changes",
+            largest_percent_changes(routes, cli.top, cli.min_base),
+        ),
+        route_table("New Routes", comparison.new_routes()),
+        route_table("Dropped Routes", comparison.dropped_routes()),
+        airport_table(
+            "Largest Airport Increases",
+            largest_increases(airports, cli.top),
+        ),
+        airport_table(
+            "Largest Airport Decreases",
+            largest_decreases(airports, cli.top),
+        ),
+        airport_table(
+            "Largest Airport Percent Changes",
+            largest_percent_changes(airports, cli.top, cli.min_base),
+        ),
+    ]
+}
+
+/// Most concentrated markets first, the busiest of equally concentrated ones
+/// leading.
+fn by_concentration<K: Ord>(markets: &BTreeMap<K, Market>) -> Vec<(&K, &Market)> {
+    let mut ranked: Vec<(&K, &Market)> = markets.iter().collect();
+    ranked.sort_by(|a, b| {
+        b.1.hhi()
+            .total_cmp(&a.1.hhi())
+            .then_with(|| b.1.total.cmp(&a.1.total))
+            .then_with(|| a.0.cmp(b.0))
+    });
+    ranked
+}
+
+fn market_row(mut row: Vec<Value>, market: &Market) -> Vec<Value> {
+    let leader = market.leader();
+    row.push(market.total.into());
+    row.push(market.shares.len().into());
+    row.push(leader.carrier.as_str().into());
+    row.push(leader.share.into());
+    row.push(market.hhi().into());
+    row.push(market.effective_competitors().into());
+    row
+}
+
+fn market_table(cli: &Cli, kind: MarketKind, flights: &[FlightData]) -> Table {
+    let stats = [
+        "flights",
+        "carriers",
+        "leader",
+        "leader share",
+        "hhi",
+        "effective carriers",
+    ];
+    match kind {
+        MarketKind::Airport | MarketKind::Gateway => {
+            let markets = if kind == MarketKind::Airport {
+                airport_markets(flights, cli.class)
+            } else {
+                gateway_markets(flights, cli.class)
+            };
+            let headers: Vec<&str> = ["airport"].into_iter().chain(stats).collect();
+            let mut table = Table::new("Carrier Concentration by Airport", &headers);
+            for (airport, market) in by_concentration(&markets).into_iter().take(cli.top) {
+                table.push(market_row(vec![airport.as_str().into()], market));
+            }
+            table
+        }
+        MarketKind::Route => {
+            let markets = route_markets(flights, cli.class);
+            let headers: Vec<&str> = ["us airport", "foreign airport"]
+                .into_iter()
+                .chain(stats)
+                .collect();
+            let mut table = Table::new("Carrier Concentration by Route", &headers);
+            for ((us, foreign), market) in by_concentration(&markets).into_iter().take(cli.top) {
+                table.push(market_row(
+                    vec![us.as_str().into(), foreign.as_str().into()],
+                    market,
+                ));
+            }
+            table
+        }
+    }
+}
+
+fn layer_tables(cli: &Cli, flights: &[FlightData]) -> Vec<Table> {
+    let multiplex = build_multiplex(flights, cli.class, cli.layer_by);
+
+    let mut layers = Table::new(
+        &format!("Top {} Layers by Flights", cli.top),
+        &[
+            "layer",
+            "nodes",
+            "edges",
+            "flights",
+            "components",
+            "largest component",
+            "hub",
+            "hub flights",
+        ],
+    );
+    for summary in multiplex.layer_summaries(1).into_iter().take(cli.top) {
+        let (hub, hub_flights) = summary.hubs[0].clone();
+        layers.push(vec![
+            summary.layer.into(),
+            summary.nodes.into(),
+            summary.edges.into(),
+            summary.flights.into(),
+            summary.components.into(),
+            summary.largest_component.into(),
+            hub.into(),
+            hub_flights.into(),
+        ]);
+    }
+
+    let mut overlaps = Table::new(
+        "Most Shared Routes Between Layers",
+        &["layer", "other layer", "shared routes", "jaccard"],
+    );
+    for overlap in multiplex.overlaps().into_iter().take(cli.top) {
+        overlaps.push(vec![
+            overlap.first.into(),
+            overlap.second.into(),
+            overlap.shared.into(),
+            overlap.jaccard.into(),
+        ]);
+    }
+
+    let mut participation = Table::new(
+        &format!("Top {} Airports by Multiplex Participation", cli.top),
+        &["airport", "participation"],
+    );
+    for (airport, score) in top_k(&multiplex.participation(), cli.top, |_| true) {
+        participation.push(vec![airport.into(), score.into()]);
+    }
+
+    vec![layers, overlaps, participation]
+}
+
+fn group_tables(cli: &Cli, flights: &[FlightData]) -> Vec<Table> {
+    let graphs = build_group_graphs(flights, cli.class);
+
+    let mut networks = Table::new(
+        "Carrier Group Networks",
+        &["group", "nodes", "edges", "flights", "components"],
+    );
+    for (name, graph) in [("US", &graphs.us), ("Foreign", &graphs.foreign)] {
+        let flights: u64 = graph.routes().iter().map(|(_, w)| *w).sum();
+        networks.push(vec![
+            name.into(),
+            graph.node_count().into(),
+            graph.edge_count().into(),
+            flights.into(),
+            graph.component_ids().len().into(),
+        ]);
+    }
+
+    let splits = route_splits(&graphs);
+    let services = [
+        (RouteService::UsOnly, "US only", "Busiest US-Only Routes"),
+        (
+            RouteService::ForeignOnly,
+            "Foreign only",
+            "Busiest Foreign-Only Routes",
+        ),
+        (RouteService::Both, "Both", "Busiest Routes Served by Both"),
+    ];
+    let mut service = Table::new(
+        "Routes by Carrier Group",
+        &["served by", "routes", "us flights", "foreign flights"],
+    );
+    for (kind, name, _) in services {
+        let routes: Vec<&RouteSplit> = splits.iter().filter(|s| s.service() == kind).collect();
+        service.push(vec![
+            name.into(),
+            routes.len().into(),
+            routes.iter().map(|s| s.us).sum::<u64>().into(),
+            routes.iter().map(|s| s.foreign).sum::<u64>().into(),
+        ]);
+    }
+
+    let mut tables = vec![networks, service];
+    for (kind, _, title) in services {
+        let mut table = Table::new(
+            title,
+            &[
+                "source",
+                "target",
+                "us flights",
+                "foreign flights",
+                "us share",
+            ],
+        );
+        for split in splits.iter().filter(|s| s.service() == kind).take(cli.top) {
+            table.push(vec![
+                split.route.0.as_str().into(),
+                split.route.1.as_str().into(),
+                split.us.into(),
+                split.foreign.into(),
+                split.us_share().into(),
+            ]);
+        }
+        tables.push(table);
+    }
+
+    let shares = airport_group_shares(flights, cli.class, cli.period);
+    let totals: HashMap<String, u64> = shares
+        .iter()
+        .map(|(airport, series)| {
+            let total = series.iter().map(|s| s.us + s.foreign).sum();
+            (airport.clone(), total)
+        })
+        .collect();
+    let mut over_time = Table::new(
+        &format!("US Carrier Share at the {} Busiest Airports", cli.top),
+        &[
+            "airport",
+            "period",
+            "us flights",
+            "foreign flights",
+            "us share",
+        ],
+    );
+    for (airport, _) in top_k(&totals, cli.top, |_| true) {
+        for share in &shares[&airport] {
+            over_time.push(vec![
+                airport.as_str().into(),
+                share.period.to_string().into(),
+                share.us.into(),
+                share.foreign.into(),
+                share.us_share().map_or_else(|| "".into(), Value::from),
+            ]);
+        }
+    }
+    tables.push(over_time);
+
+    tables
+}
+
+fn shape_name(shape: NetworkShape) -> &'static str {
+    match shape {
+        NetworkShape::HubAndSpoke => "hub-and-spoke",
+        NetworkShape::PointToPoint => "point-to-point",
+        NetworkShape::TooSmall => "too small",
+    }
+}
+
+fn hub_tables(cli: &Cli, flights: &[FlightData]) -> Vec<Table> {
+    let options = HubOptions {
+        core_coverage: cli.core_coverage,
+        max_core_fraction: cli.max_core_fraction,
+        ..HubOptions::default()
+    };
+    let profiles = carrier_profiles(flights, cli.class, &options);
+
+    let mut shapes = Table::new("Carrier Network Shapes", &["shape", "carriers", "flights"]);
+    for shape in [
+        NetworkShape::HubAndSpoke,
+        NetworkShape::PointToPoint,
+        NetworkShape::TooSmall,
+    ] {
+        let matching: Vec<&NetworkProfile> = profiles.iter().filter(|p| p.shape == shape).collect();
+        shapes.push(vec![
+            shape_name(shape).into(),
+            matching.len().into(),
+            matching.iter().map(|p| p.flights).sum::<u64>().into(),
+        ]);
+    }
+
+    let mut carriers = Table::new(
+        &format!("Top {} Carriers by Flights", cli.top),
+        &[
+            "carrier",
+            "airports",
+            "routes",
+            "flights",
+            "hubs",
+            "top-1 share",
+            "top-3 share",
+            "hub share",
+            "core fraction",
+            "centralization",
+            "shape",
+        ],
+    );
+    for profile in profiles.iter().take(cli.top) {
+        let hubs: Vec<&str> = profile.hubs.iter().map(|h| h.airport.as_str()).collect();
+        carriers.push(vec![
+            profile.name.as_str().into(),
+            profile.airports.into(),
+            profile.routes.into(),
+            profile.flights.into(),
+            hubs.join(" ").into(),
+            profile.top1_share.into(),
+            profile.top3_share.into(),
+            profile.hub_share.into(),
+            profile.core_fraction.into(),
+            profile.centralization.into(),
+            shape_name(profile.shape).into(),
+        ]);
+    }
+
+    vec![shapes, carriers]
+}
+
+fn communities_table(cli: &Cli, graph: &Graph) -> Table {
+    let communities = graph.louvain(&LouvainOptions {
+        resolution: cli.resolution,
+        ..LouvainOptions::default()
+    });
+
+    let title = format!(
+        "Top {} of {} Communities (Modularity {:.4})",
+        cli.top, communities.count, communities.modularity
+    );
+    let mut table = Table::new(
+        &title,
+        &[
+            "community",
+            "airports",
+            "internal flights",
+            "total flights",
+            "top airports",
+        ],
+    );
+    for summary in graph
+        .community_summaries(&communities, 3)
+        .into_iter()
+        .take(cli.top)
+    {
+        let top: Vec<&str> = summary
+            .top_airports
+            .iter()
+            .map(|(code, _)| code.as_str())
+            .collect();
+        table.push(vec![
+            (summary.community + 1).into(),
+            summary.airports.into(),
+            summary.internal_flights.into(),
+            summary.total_flights.into(),
+            top.join(" ").into(),
+        ]);
+    }
+    table
+}
+
+fn cut_tables(cli: &Cli, graph: &Graph) -> Vec<Table> {
+    let points = graph.articulation_points();
+    let title = format!("Top {} of {} Articulation Airports", cli.top, points.len());
+    let mut airports = Table::new(
+        &title,
+        &["airport", "cut off", "pieces", "largest cut-off piece"],
+    );
+    for point in points.iter().take(cli.top) {
+        airports.push(vec![
+            point.airport.as_str().into(),
+            point.cut_off.into(),
+            point.pieces.len().into(),
+            point.pieces[1].into(),
+        ]);
+    }
+
+    let bridges = graph.bridges();
+    let title = format!("Top {} of {} Bridge Routes", cli.top, bridges.len());
+    let mut routes = Table::new(&title, &["from", "to", "flights", "cut off"]);
+    for bridge in bridges.iter().take(cli.top) {
+        routes.push(vec![
+            bridge.from.as_str().into(),
+            bridge.to.as_str().into(),
+            bridge.flights.into(),
+            bridge.cut_off.into(),
+        ]);
+    }
+
+    vec![airports, routes]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn parse(args: &[&str]) -> Result<Cli, CliError> {
+        Cli::parse(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn test_parse_defaults() {
+        let cli = parse(&[]).unwrap();
+
+        assert_eq!(cli.command, Command::Report);
+        assert_eq!(cli.inputs, vec![DEFAULT_INPUT.to_string()]);
+        assert_eq!(cli.top, 5);
+        assert_eq!(cli.format, OutputFormat::Text);
+        assert_eq!(cli.mode, ParseMode::Strict); // Same default as ReadOptions
+        assert_eq!(parse(&["--lenient"]).unwrap().mode, ParseMode::Lenient);
+    }
+
+    #[test]
+    fn test_parse_subcommand_and_options() {
+        let cli = parse(&[
+            "-i",
+            "a.csv",
+            "--input",
+            "b.csv",
+            "centrality",
+            "--top",
+            "10",
+            "--year",
+            "2001-2008",
+            "--month",
+            "6",
+            "--class",
+            "charter",
+            "--format",
+            "json",
+            "--measure",
+            "pagerank",
+        ])
+        .unwrap();
+
+        assert_eq!(cli.command, Command::Centrality);
+        assert_eq!(cli.inputs, vec!["a.csv".to_string(), "b.csv".to_string()]);
+        assert_eq!(cli.top, 10);
+        assert_eq!(cli.filter.years, Some(2001..=2008));
+        assert_eq!(cli.filter.months, Some(6..=6));
+        assert_eq!(cli.class, ServiceClass::Charter);
+        assert_eq!(cli.format, OutputFormat::Json);
+        assert_eq!(cli.measure, CentralityMeasure::PageRank);
+
+        let filtered = parse(&[
+            "--min-flights",
+            "3",
+            "--carrier",
+            "AA,UA",
+            "--carrier",
+            "DL",
+            "--carrier-group",
+            "us",
+            "--class",
+            "scheduled",
+        ])
+        .unwrap();
+        assert_eq!(
+            filtered.filter,
+            RecordFilter::new()
+                .carriers(["AA", "UA", "DL"])
+                .carrier_group(CarrierGroup::Us)
+                .min_flights(ServiceClass::Scheduled, 3)
+        );
+
+        let path = parse(&["path", "BOS", "MAJ", "--all"]).unwrap();
+        assert_eq!(
+            path.command,
+            Command::Path {
+                from: "BOS".to_string(),
+                to: "MAJ".to_string()
+            }
+        );
+        assert!(path.all_paths);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse(&["bogus"]).is_err());
+        assert!(parse(&["path", "BOS"]).is_err());
+        assert!(parse(&["path", "BOS", "MAJ", "--all", "--cost", "inverse-flights"]).is_err());
+        assert!(parse(&["centrality", "--measure", "closeness", "--directed"]).is_err());
+        assert!(parse(&["centrality", "--measure", "pagerank", "--cost", "hops"]).is_ok());
+        let weighted_pagerank = [
+            "centrality",
+            "--measure",
+            "pagerank",
+            "--cost",
+            "inverse-flights",
+        ];
+        assert!(parse(&weighted_pagerank).is_err());
+        assert!(parse(&["centrality", "--measure", "wasserman-faust", "--directed"]).is_ok());
+        assert!(parse(&["timeline", "--period", "0"]).is_err());
+        assert!(parse(&["series"]).is_err());
+        assert!(parse(&["compare", "2019"]).is_err());
+        assert!(parse(&["compare", "2019", "2020-13"]).is_err());
+        assert!(parse(&["market", "carrier"]).is_err());
+        assert!(parse(&["layers", "--layer-by", "alliance"]).is_err());
+        assert!(parse(&["communities", "--resolution", "0"]).is_err());
+        assert!(parse(&["hubs", "--max-core", "1.5"]).is_err());
+        assert!(parse(&["series", "country"]).is_err());
+        assert!(parse(&["--carrier-group", "both"]).is_err());
+        assert!(parse(&["--top"]).is_err());
+        assert!(parse(&["--top", "many"]).is_err());
+        assert!(parse(&["--year", "2008-2001"]).is_err());
+        assert!(parse(&["--month", "0-3"]).is_err());
+        assert!(parse(&["--format", "xml"]).is_err());
+        assert!(parse(&["stats", "extra"]).is_err());
+        assert_eq!(parse(&["stats", "--help"]).unwrap().command, Command::Help);
+    }
+
+    const SAMPLE_CSV: &str = "\
+data_dte,Year,Month,usg_apt_id,usg_apt,usg_wac,fg_apt_id,fg_apt,fg_wac,airlineid,carrier,carriergroup,type,Scheduled,Charter,Total
+01/01/2019,2019,1,1,BOS,13,2,LHR,493,1,AA,1,Departures,10,0,10
+01/01/2019,2019,1,3,GUM,5,2,LHR,493,1,UA,1,Departures,3,0,3
+01/01/2019,2019,1,3,GUM,5,4,MAJ,844,2,PFQ,1,Departures,0,5,5
+01/01/2020,2020,1,1,BOS,13,4,MAJ,844,2,PFQ,1,Departures,0,9,9
+";
+
+    /// A CSV written to a temp file of its own, removed on drop even if the
+    /// test panics.
+    struct SampleFile(std::path::PathBuf);
+
+    impl SampleFile {
+        fn new() -> Self {
+            Self::with_contents(SAMPLE_CSV)
+        }
+
+        fn with_contents(contents: &str) -> Self {
+            static NEXT: AtomicUsize = AtomicUsize::new(0);
+            let name = format!(
+                "final_project_cli_{}_{}.csv",
+                std::process::id(),
+                NEXT.fetch_add(1, Ordering::Relaxed)
+            );
+            let path = std::env::temp_dir().join(name);
+            std::fs::write(&path, contents).unwrap();
+            SampleFile(path)
+        }
+    }
+
+    impl Drop for SampleFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    /// Runs the CLI with `args` on a fresh copy of `SAMPLE_CSV`.
+    fn run_args(args: &[&str]) -> String {
+        let file = SampleFile::new();
+        let mut all = vec!["--input", file.0.to_str().unwrap()];
+        all.extend_from_slice(args);
+        let mut out = Vec::new();
+        run(&parse(&all).unwrap(), &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_run_strict_by_default() {
+        let file = SampleFile::with_contents(&format!(
+            "{}01/01/2019,2019,1,1,BOS,13,2,LHR,493,1,AA,1,Departures,1,0,x\n",
+            SAMPLE_CSV
+        ));
+        let input = file.0.to_str().unwrap();
+        let mut out = Vec::new();
+
+        let strict = parse(&["stats", "--input", input]).unwrap();
+        let err = run(&strict, &mut out).unwrap_err();
+        assert!(err.to_string().starts_with("line 6:"));
+
+        let lenient = parse(&["stats", "--input", input, "--lenient"]).unwrap();
+        run(&lenient, &mut out).unwrap();
+    }
+
+    #[test]
+    fn test_run_top_airports() {
+        let top = run_args(&["top-airports", "--format", "csv", "--class", "charter"]);
+        assert_eq!(top, "airport,flights\nBOS,9\nGUM,5\n");
+
+        let pfq = run_args(&[
+            "top-airports",
+            "--format",
+            "csv",
+            "--carrier",
+            "PFQ",
+            "--year",
+            "2020",
+        ]);
+        assert_eq!(pfq, "airport,flights\nBOS,9\n");
+    }
+
+    #[test]
+    fn test_run_path() {
+        let path = run_args(&["path", "BOS", "MAJ", "--year", "2019", "--format", "csv"]);
+        assert_eq!(path, "legs,itinerary\n3,BOS > LHR > GUM > MAJ\n");
+
+        let all = run_args(&["path", "BOS", "GUM", "--all", "--format", "csv"]);
+        assert_eq!(
+            all,
+            "legs,itinerary\n2,BOS > LHR > GUM\n2,BOS > MAJ > GUM\n"
+        );
+        let first = run_args(&[
+            "path", "BOS", "GUM", "--all", "--top", "1", "--format", "csv",
+        ]);
+        assert_eq!(first, "legs,itinerary\n2,BOS > LHR > GUM\n");
+    }
+
+    #[test]
+    fn test_run_timeline() {
+        let timeline = run_args(&["timeline", "--format", "csv", "--period", "12"]);
+        assert_eq!(
+            timeline,
+            "period,records,nodes,edges,flights,components,largest component,top airport,score\n\
+             2019,3,4,3,18,1,4,GUM,2.5\n\
+             2020,1,2,1,9,1,2,BOS,1\n"
+        );
+    }
+
+    #[test]
+    fn test_run_series() {
+        let series = run_args(&["series", "carrier", "--format", "csv", "--carrier", "UA,AA"]);
+        assert_eq!(
+            series,
+            "carrier,month,flights\nAA,2019-01,10\nUA,2019-01,3\n"
+        );
+
+        let series = run_args(&["series", "route", "--format", "csv", "--us-airport", "BOS"]);
+        assert_eq!(series.lines().count(), 1 + 2 * 13);
+        assert!(series.contains("\nBOS,LHR,2019-02,0\n"));
+    }
+
+    #[test]
+    fn test_run_compare() {
+        let compare = run_args(&[
+            "compare",
+            "2019",
+            "2020",
+            "--format",
+            "csv",
+            "--min-base",
+            "5",
+        ]);
+        let header = "source,target,before,after,change,percent\n";
+        assert_eq!(compare.matches(header).count(), 5);
+        assert!(compare.starts_with(&format!(
+            "\"Largest Route Increases, 2019 to 2020\"\n{}BOS,MAJ,0,9,9,\n\n\
+             \"Largest Route Decreases, 2019 to 2020\"\n{}BOS,LHR,10,0,-10,-100\n",
+            header, header
+        )));
+        assert!(compare.ends_with("\n\n\"Largest Airport Percent Changes, 2019 to 2020\"\n\
+             airport,before,after,change,percent\nGUM,8,0,-8,-100\nLHR,13,0,-13,-100\nMAJ,5,9,4,80\nBOS,10,9,-1,-10\n"));
+    }
+
+    #[test]
+    fn test_run_market() {
+        let market = run_args(&["market", "route", "--format", "csv", "--top", "1"]);
+        assert_eq!(
+            market,
+            "us airport,foreign airport,flights,carriers,leader,leader share,hhi,effective carriers\n\
+             BOS,LHR,10,1,AA,1,10000,1\n"
+        );
+    }
+
+    #[test]
+    fn test_run_layers() {
+        let layers = run_args(&["layers", "--format", "csv", "--top", "2"]);
+        assert!(layers.starts_with(
+            "Top 2 Layers by Flights\n\
+             layer,nodes,edges,flights,components,largest component,hub,hub flights\n\
+             PFQ,3,2,14,1,3,MAJ,14\n\
+             AA,2,1,10,1,2,BOS,10\n\n\
+             Most Shared Routes Between Layers\n\
+             layer,other layer,shared routes,jaccard\n\n\
+             Top 2 Airports by Multiplex Participation\n\
+             airport,participation\n"
+        ));
+    }
+
+    #[test]
+    fn test_run_groups() {
+        let groups = run_args(&["groups", "--format", "csv", "--top", "1"]);
+        assert!(groups.starts_with(
+            "Carrier Group Networks\n\
+             group,nodes,edges,flights,components\nUS,4,4,27,1\nForeign,0,0,0,0\n\n\
+             Routes by Carrier Group\n\
+             served by,routes,us flights,foreign flights\nUS only,4,27,0\n"
+        ));
+        assert!(groups.ends_with(
+            "\n\nUS Carrier Share at the 1 Busiest Airports\n\
+             airport,period,us flights,foreign flights,us share\nBOS,2019,10,0,1\nBOS,2020,9,0,1\n"
+        ));
+    }
+
+    #[test]
+    fn test_run_hubs() {
+        let hubs = run_args(&["hubs", "--format", "csv", "--top", "1"]);
+        assert_eq!(
+            hubs,
+            "Carrier Network Shapes\n\
+             shape,carriers,flights\nhub-and-spoke,0,0\npoint-to-point,0,0\ntoo small,3,27\n\n\
+             Top 1 Carriers by Flights\n\
+             carrier,airports,routes,flights,hubs,top-1 share,top-3 share,hub share,\
+             core fraction,centralization,shape\n\
+             PFQ,3,2,14,,1,1,0,0.3333333333333333,1,too small\n"
+        );
+    }
+
+    #[test]
+    fn test_run_communities() {
+        let communities = run_args(&["communities", "--format", "csv"]);
+        assert_eq!(
+            communities,
+            "community,airports,internal flights,total flights,top airports\n\
+             1,2,10,22,BOS LHR\n\
+             2,2,5,17,MAJ GUM\n"
+        );
+    }
+
+    #[test]
+    fn test_run_cuts() {
+        let cuts = run_args(&["cuts", "--format", "csv", "--year", "2019"]);
+        assert_eq!(
+            cuts,
+            "Top 5 of 2 Articulation Airports\n\
+             airport,cut off,pieces,largest cut-off piece\nGUM,1,2,1\nLHR,1,2,1\n\n\
+             Top 5 of 3 Bridge Routes\n\
+             from,to,flights,cut off\nLHR,GUM,3,2\nGUM,MAJ,5,1\nLHR,BOS,10,1\n"
+        );
+    }
+
+    #[test]
+    fn test_run_weighted_centrality() {
+        let hops = run_args(&["centrality", "--year", "2019", "--format", "csv"]);
+        let weighted = run_args(&[
+            "centrality",
+            "--cost",
+            "inverse-flights",
+            "--year",
+            "2019",
+            "--format",
+            "csv",
+        ]);
+        assert_eq!(
+            hops,
+            "airport,score\nGUM,2.5\nLHR,2.5\nBOS,1.8333333333333333\nMAJ,1.8333333333333333\n"
+        );
+        // Weighting by flights pulls BOS, on the busy BOS-LHR route, ahead of GUM
+        assert_eq!(
+            weighted,
+            "airport,score\nLHR,14.875\nBOS,13.88663967611336\n\
+             GUM,10.307692307692307\nMAJ,8.453947368421053\n"
+        );
+
+        let file = SampleFile::new();
+        let cli = parse(&[
+            "centrality",
+            "--cost",
+            "neg-log-share",
+            "--input",
+            file.0.to_str().unwrap(),
+        ]);
+        let err = run(&cli.unwrap(), &mut Vec::new()).unwrap_err();
+        assert!(err.to_string().contains("undefined for neg-log-share"));
+    }
+
+    #[test]
+    fn test_run_report_json() {
+        let report = run_args(&["report", "--format", "json", "--year", "2019"]);
+        let tables: Vec<serde_json::Value> = serde_json::from_str(&report).unwrap();
+        let titles: Vec<&str> = tables
+            .iter()
+            .map(|t| t["title"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            titles,
+            [
+                "Graph Statistics",
+                "Top 5 Busiest Airports",
+                "Connected Components",
+                "Top 5 Airports by Centrality (Largest Component)",
+            ]
+        );
+        assert_eq!(tables[1]["rows"][0]["airport"], "BOS");
+        assert_eq!(tables[1]["rows"][0]["flights"], 10);
+    }
+
+    #[test]
+    fn test_run_export() {
+        let export = run_args(&["export", "--format", "csv", "--year", "2019"]);
+        assert_eq!(
+            export,
+            "source,target,flights\nBOS,LHR,10\nGUM,MAJ,5\nGUM,LHR,3\n"
+        );
+    }
+}
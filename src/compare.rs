@@ -0,0 +1,222 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::data::{FlightData, ServiceClass};
+use crate::graph::{Breakdowns, Graph, NodeId};
+use crate::timeline::Period;
+
+/// Flights of one route or airport in two periods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change<K> {
+    pub key: K,
+    pub before: u64,
+    pub after: u64,
+}
+
+impl<K> Change<K> {
+    pub fn delta(&self) -> i64 {
+        self.after as i64 - self.before as i64
+    }
+
+    /// Change relative to the earlier period, in percent. `None` when there
+    /// were no flights to compare against.
+    pub fn percent(&self) -> Option<f64> {
+        if self.before == 0 {
+            None
+        } else {
+            Some(self.delta() as f64 / self.before as f64 * 100.0)
+        }
+    }
+}
+
+/// Route and airport traffic of two periods side by side.
+///
+/// Routes are keyed by their endpoints in code order, since the aggregated
+/// graph does not keep direction. Airports count the flights on every route
+/// they serve, at either end.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeriodComparison {
+    pub before: Period,
+    pub after: Period,
+    pub routes: Vec<Change<(String, String)>>,
+    pub airports: Vec<Change<String>>,
+}
+
+/// The airport graph of the flights dated within `period`.
+fn period_graph(flights: &[FlightData], class: ServiceClass, period: Period) -> Graph {
+    let mut graph = Graph::new();
+    for flight in flights.iter().filter(|f| period.contains(f.date)) {
+        graph.add_flight(flight, class, Breakdowns::default());
+    }
+    graph
+}
+
+/// Total flights per route; `Graph::routes` gives undirected routes with
+/// their endpoints in code order, so both periods key a route the same way.
+fn route_totals(graph: &Graph) -> BTreeMap<(String, String), u64> {
+    graph.routes().into_iter().collect()
+}
+
+fn airport_totals(graph: &Graph) -> BTreeMap<String, u64> {
+    graph
+        .strengths()
+        .into_iter()
+        .enumerate()
+        .map(|(id, total)| (graph.node_code(id as NodeId).to_string(), total))
+        .collect()
+}
+
+/// Pairs up the totals of both periods, with zeros for keys missing from one.
+fn changes<K: Ord + Clone>(before: &BTreeMap<K, u64>, after: &BTreeMap<K, u64>) -> Vec<Change<K>> {
+    let keys: BTreeSet<&K> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .map(|key| Change {
+            key: key.clone(),
+            before: before.get(key).copied().unwrap_or(0),
+            after: after.get(key).copied().unwrap_or(0),
+        })
+        .collect()
+}
+
+/// Largest changes first by `order`, ties broken by key.
+fn ranked<K: Ord + Clone>(
+    changes: &[Change<K>],
+    k: usize,
+    keep: impl Fn(&Change<K>) -> bool,
+    order: impl Fn(&Change<K>, &Change<K>) -> Ordering,
+) -> Vec<Change<K>> {
+    let mut selected: Vec<&Change<K>> = changes.iter().filter(|c| keep(c)).collect();
+    selected.sort_by(|a, b| order(a, b).then_with(|| a.key.cmp(&b.key)));
+    selected.into_iter().take(k).cloned().collect()
+}
+
+/// Compares the traffic of `class` flights between two periods.
+pub fn compare_periods(
+    flights: &[FlightData],
+    class: ServiceClass,
+    before: Period,
+    after: Period,
+) -> PeriodComparison {
+    let (old, new) = (
+        period_graph(flights, class, before),
+        period_graph(flights, class, after),
+    );
+
+    PeriodComparison {
+        before,
+        after,
+        routes: changes(&route_totals(&old), &route_totals(&new)),
+        airports: changes(&airport_totals(&old), &airport_totals(&new)),
+    }
+}
+
+/// The `k` largest gains in flights.
+pub fn largest_increases<K: Ord + Clone>(changes: &[Change<K>], k: usize) -> Vec<Change<K>> {
+    ranked(
+        changes,
+        k,
+        |c| c.delta() > 0,
+        |a, b| b.delta().cmp(&a.delta()),
+    )
+}
+
+/// The `k` largest losses in flights.
+pub fn largest_decreases<K: Ord + Clone>(changes: &[Change<K>], k: usize) -> Vec<Change<K>> {
+    ranked(
+        changes,
+        k,
+        |c| c.delta() < 0,
+        |a, b| a.delta().cmp(&b.delta()),
+    )
+}
+
+/// The `k` largest relative changes in either direction, among keys with at
+/// least `min_before` flights in the earlier period so that tiny routes do
+/// not crowd out the list.
+pub fn largest_percent_changes<K: Ord + Clone>(
+    changes: &[Change<K>],
+    k: usize,
+    min_before: u64,
+) -> Vec<Change<K>> {
+    let magnitude = |c: &Change<K>| c.percent().map_or(0.0, f64::abs);
+    ranked(
+        changes,
+        k,
+        |c| c.before >= min_before.max(1) && c.delta() != 0,
+        |a, b| magnitude(b).total_cmp(&magnitude(a)),
+    )
+}
+
+impl PeriodComparison {
+    /// Routes flown in the later period but not the earlier one, busiest first.
+    pub fn new_routes(&self) -> Vec<Change<(String, String)>> {
+        ranked(
+            &self.routes,
+            usize::MAX,
+            |c| c.before == 0,
+            |a, b| b.after.cmp(&a.after),
+        )
+    }
+
+    /// Routes flown in the earlier period but not the later one, busiest first.
+    pub fn dropped_routes(&self) -> Vec<Change<(String, String)>> {
+        ranked(
+            &self.routes,
+            usize::MAX,
+            |c| c.after == 0,
+            |a, b| b.before.cmp(&a.before),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn period(text: &str) -> Period {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn test_compare_periods() {
+        let flights = vec![
+            FlightData::for_test(2019, 1, "JFK", "LHR", "AA", 100, 0),
+            FlightData::for_test(2019, 2, "JFK", "CDG", "AF", 50, 0),
+            FlightData::for_test(2019, 2, "MIA", "GRU", "AA", 5, 0),
+            FlightData::for_test(2020, 1, "JFK", "LHR", "AA", 40, 0),
+            FlightData::for_test(2020, 1, "JFK", "CDG", "AF", 60, 0),
+            FlightData::for_test(2020, 3, "MIA", "BOG", "AV", 0, 20),
+            FlightData::for_test(2021, 1, "JFK", "LHR", "AA", 999, 0),
+        ];
+        let comparison = compare_periods(
+            &flights,
+            ServiceClass::Total,
+            period("2019"),
+            period("2020"),
+        );
+
+        let route = |a: &str, b: &str| (a.to_string(), b.to_string());
+        let increases = largest_increases(&comparison.routes, 5);
+        assert_eq!(increases[0].key, route("BOG", "MIA"));
+        assert_eq!(increases[1].key, route("CDG", "JFK"));
+        assert_eq!(increases[1].percent(), Some(20.0));
+
+        let decreases = largest_decreases(&comparison.routes, 1);
+        assert_eq!(decreases[0].key, route("JFK", "LHR"));
+        assert_eq!(
+            (decreases[0].delta(), decreases[0].percent()),
+            (-60, Some(-60.0))
+        );
+
+        let percent = largest_percent_changes(&comparison.routes, 5, 10);
+        let keys: Vec<_> = percent.iter().map(|c| c.key.clone()).collect();
+        assert_eq!(keys, [route("JFK", "LHR"), route("CDG", "JFK")]); // MIA-GRU too small
+
+        assert_eq!(comparison.new_routes()[0].key, route("BOG", "MIA"));
+        assert_eq!(comparison.dropped_routes()[0].key, route("GRU", "MIA"));
+
+        let jfk = comparison.airports.iter().find(|c| c.key == "JFK").unwrap();
+        assert_eq!((jfk.before, jfk.after), (150, 100));
+        assert_eq!(largest_increases(&comparison.airports, 1)[0].key, "BOG");
+    }
+}
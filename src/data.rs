@@ -0,0 +1,886 @@
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+use csv::{Reader, ReaderBuilder, StringRecord};
+
+use crate::filter::RecordFilter;
+
+/// One row of the departures extract: flights by one carrier from a US
+/// gateway to a foreign airport in a single month.
+#[derive(Debug, Clone)]
+pub struct FlightData {
+    pub date: YearMonth,
+    pub year: u32,
+    pub month: u32,
+    pub us_airport_id: u32,
+    pub us_airport: String,
+    pub us_wac: u32,
+    pub foreign_airport_id: u32,
+    pub foreign_airport: String,
+    pub foreign_wac: u32,
+    pub airline_id: u32,
+    pub carrier: String,
+    pub carrier_group: CarrierGroup,
+    pub flight_type: FlightType,
+    pub scheduled_flights: u32,
+    pub charter_flights: u32,
+    pub total_flights: u32,
+}
+
+impl FlightData {
+    /// Number of flights on this row for the given class of service.
+    pub fn flights(&self, class: ServiceClass) -> u32 {
+        match class {
+            ServiceClass::Scheduled => self.scheduled_flights,
+            ServiceClass::Charter => self.charter_flights,
+            ServiceClass::Total => self.total_flights,
+        }
+    }
+}
+
+#[cfg(test)]
+impl FlightData {
+    /// Builds a US-carrier departure row with placeholder IDs, for tests.
+    pub(crate) fn for_test(
+        year: u32,
+        month: u32,
+        us_airport: &str,
+        foreign_airport: &str,
+        carrier: &str,
+        scheduled_flights: u32,
+        charter_flights: u32,
+    ) -> Self {
+        Self {
+            date: YearMonth::new(year, month).unwrap(),
+            year,
+            month,
+            us_airport_id: 0,
+            us_airport: us_airport.to_string(),
+            us_wac: 0,
+            foreign_airport_id: 0,
+            foreign_airport: foreign_airport.to_string(),
+            foreign_wac: 0,
+            airline_id: 0,
+            carrier: carrier.to_string(),
+            carrier_group: CarrierGroup::Us,
+            flight_type: FlightType::Departures,
+            scheduled_flights,
+            charter_flights,
+            total_flights: scheduled_flights + charter_flights,
+        }
+    }
+}
+
+/// Which flight counts an analysis should use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ServiceClass {
+    Scheduled,
+    Charter,
+    #[default]
+    Total,
+}
+
+/// A calendar month, ordered chronologically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct YearMonth {
+    pub year: u32,
+    pub month: u32,
+}
+
+impl YearMonth {
+    pub fn new(year: u32, month: u32) -> Option<Self> {
+        if (1..=12).contains(&month) {
+            Some(Self { year, month })
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for YearMonth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}", self.year, self.month)
+    }
+}
+
+impl FromStr for YearMonth {
+    type Err = ();
+
+    /// Parses the extract's `MM/DD/YYYY` dates as well as ISO `YYYY-MM[-DD]`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (year, month) = if s.contains('/') {
+            let parts: Vec<&str> = s.split('/').collect();
+            match parts.as_slice() {
+                [month, day, year] if day.parse::<u32>().is_ok() => (*year, *month),
+                _ => return Err(()),
+            }
+        } else {
+            let parts: Vec<&str> = s.split('-').collect();
+            match parts.as_slice() {
+                [year, month] => (*year, *month),
+                [year, month, day] if day.parse::<u32>().is_ok() => (*year, *month),
+                _ => return Err(()),
+            }
+        };
+
+        let year = year.parse().map_err(|_| ())?;
+        let month = month.parse().map_err(|_| ())?;
+        YearMonth::new(year, month).ok_or(())
+    }
+}
+
+/// Nationality of the reporting carrier (`carriergroup`: 1 = US, 0 = foreign).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CarrierGroup {
+    Us,
+    Foreign,
+}
+
+impl FromStr for CarrierGroup {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "1" => Ok(CarrierGroup::Us),
+            "0" => Ok(CarrierGroup::Foreign),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FlightType {
+    Departures,
+    Arrivals,
+}
+
+impl FromStr for FlightType {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "Departures" => Ok(FlightType::Departures),
+            "Arrivals" => Ok(FlightType::Arrivals),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Header names used to locate each `FlightData` field in the CSV.
+///
+/// The default matches the BTS T-100 international departures extract.
+#[derive(Debug, Clone)]
+pub struct ColumnMapping {
+    pub date: String,
+    pub year: String,
+    pub month: String,
+    pub us_airport_id: String,
+    pub us_airport: String,
+    pub us_wac: String,
+    pub foreign_airport_id: String,
+    pub foreign_airport: String,
+    pub foreign_wac: String,
+    pub airline_id: String,
+    pub carrier: String,
+    pub carrier_group: String,
+    pub flight_type: String,
+    pub scheduled_flights: String,
+    pub charter_flights: String,
+    pub total_flights: String,
+}
+
+impl Default for ColumnMapping {
+    fn default() -> Self {
+        Self {
+            date: "data_dte".to_string(),
+            year: "Year".to_string(),
+            month: "Month".to_string(),
+            us_airport_id: "usg_apt_id".to_string(),
+            us_airport: "usg_apt".to_string(),
+            us_wac: "usg_wac".to_string(),
+            foreign_airport_id: "fg_apt_id".to_string(),
+            foreign_airport: "fg_apt".to_string(),
+            foreign_wac: "fg_wac".to_string(),
+            airline_id: "airlineid".to_string(),
+            carrier: "carrier".to_string(),
+            carrier_group: "carriergroup".to_string(),
+            flight_type: "type".to_string(),
+            scheduled_flights: "Scheduled".to_string(),
+            charter_flights: "Charter".to_string(),
+            total_flights: "Total".to_string(),
+        }
+    }
+}
+
+/// Column positions resolved from a header row.
+#[derive(Debug, Clone, Copy)]
+struct ColumnIndices {
+    date: usize,
+    year: usize,
+    month: usize,
+    us_airport_id: usize,
+    us_airport: usize,
+    us_wac: usize,
+    foreign_airport_id: usize,
+    foreign_airport: usize,
+    foreign_wac: usize,
+    airline_id: usize,
+    carrier: usize,
+    carrier_group: usize,
+    flight_type: usize,
+    scheduled_flights: usize,
+    charter_flights: usize,
+    total_flights: usize,
+}
+
+impl ColumnIndices {
+    fn resolve(headers: &StringRecord, mapping: &ColumnMapping) -> Result<Self, IngestError> {
+        // Header matching ignores case and surrounding whitespace, since
+        // different vintages of the extract are not consistent about either.
+        let positions: HashMap<String, usize> = headers
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.trim().to_lowercase(), i))
+            .collect();
+
+        let mut missing = Vec::new();
+        let mut find = |name: &str| match positions.get(&name.trim().to_lowercase()) {
+            Some(&i) => i,
+            None => {
+                missing.push(name.to_string());
+                0
+            }
+        };
+
+        let indices = Self {
+            date: find(&mapping.date),
+            year: find(&mapping.year),
+            month: find(&mapping.month),
+            us_airport_id: find(&mapping.us_airport_id),
+            us_airport: find(&mapping.us_airport),
+            us_wac: find(&mapping.us_wac),
+            foreign_airport_id: find(&mapping.foreign_airport_id),
+            foreign_airport: find(&mapping.foreign_airport),
+            foreign_wac: find(&mapping.foreign_wac),
+            airline_id: find(&mapping.airline_id),
+            carrier: find(&mapping.carrier),
+            carrier_group: find(&mapping.carrier_group),
+            flight_type: find(&mapping.flight_type),
+            scheduled_flights: find(&mapping.scheduled_flights),
+            charter_flights: find(&mapping.charter_flights),
+            total_flights: find(&mapping.total_flights),
+        };
+
+        if missing.is_empty() {
+            Ok(indices)
+        } else {
+            Err(IngestError::MissingHeaders(missing))
+        }
+    }
+}
+
+/// Error raised while ingesting the departures CSV.
+///
+/// Row-level variants carry the 1-based line number in the file and the
+/// header of the offending column.
+#[derive(Debug)]
+pub enum IngestError {
+    Csv(csv::Error),
+    MissingHeaders(Vec<String>),
+    MissingField {
+        line: u64,
+        column: String,
+    },
+    BadNumber {
+        line: u64,
+        column: String,
+        value: String,
+    },
+    BadDate {
+        line: u64,
+        column: String,
+        value: String,
+    },
+    UnknownType {
+        line: u64,
+        column: String,
+        value: String,
+    },
+    UnknownCarrierGroup {
+        line: u64,
+        column: String,
+        value: String,
+    },
+    /// The row's date column names a different month than its year and
+    /// month columns.
+    DateMismatch {
+        line: u64,
+        date: YearMonth,
+        year: u32,
+        month: u32,
+    },
+    /// A row the CSV reader itself could not decode, e.g. invalid UTF-8.
+    UnreadableRow {
+        line: u64,
+        source: csv::Error,
+    },
+}
+
+impl IngestError {
+    /// Short label for the kind of error, used to summarize skipped rows.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            IngestError::Csv(_) => "csv",
+            IngestError::MissingHeaders(_) => "missing headers",
+            IngestError::MissingField { .. } => "missing field",
+            IngestError::BadNumber { .. } => "bad number",
+            IngestError::BadDate { .. } => "bad date",
+            IngestError::UnknownType { .. } => "unknown type",
+            IngestError::UnknownCarrierGroup { .. } => "unknown carrier group",
+            IngestError::DateMismatch { .. } => "date mismatch",
+            IngestError::UnreadableRow { .. } => "unreadable row",
+        }
+    }
+}
+
+impl fmt::Display for IngestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IngestError::Csv(err) => write!(f, "CSV error: {}", err),
+            IngestError::MissingHeaders(headers) => {
+                write!(f, "missing required CSV headers: {}", headers.join(", "))
+            }
+            IngestError::MissingField { line, column } => {
+                write!(f, "line {}: missing value for column '{}'", line, column)
+            }
+            IngestError::BadNumber {
+                line,
+                column,
+                value,
+            } => {
+                write!(
+                    f,
+                    "line {}: '{}' in column '{}' is not a valid number",
+                    line, value, column
+                )
+            }
+            IngestError::BadDate {
+                line,
+                column,
+                value,
+            } => {
+                write!(
+                    f,
+                    "line {}: '{}' in column '{}' is not a valid date",
+                    line, value, column
+                )
+            }
+            IngestError::UnknownType {
+                line,
+                column,
+                value,
+            } => {
+                write!(
+                    f,
+                    "line {}: unknown flight type '{}' in column '{}'",
+                    line, value, column
+                )
+            }
+            IngestError::UnknownCarrierGroup {
+                line,
+                column,
+                value,
+            } => {
+                write!(
+                    f,
+                    "line {}: unknown carrier group '{}' in column '{}'",
+                    line, value, column
+                )
+            }
+            IngestError::DateMismatch {
+                line,
+                date,
+                year,
+                month,
+            } => {
+                write!(
+                    f,
+                    "line {}: date {} does not match year {} and month {}",
+                    line, date, year, month
+                )
+            }
+            IngestError::UnreadableRow { line, source } => {
+                write!(f, "line {}: unreadable row: {}", line, source)
+            }
+        }
+    }
+}
+
+impl Error for IngestError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            IngestError::Csv(err) | IngestError::UnreadableRow { source: err, .. } => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<csv::Error> for IngestError {
+    fn from(err: csv::Error) -> Self {
+        IngestError::Csv(err)
+    }
+}
+
+/// How `read_csv_with` treats rows that fail to parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseMode {
+    /// Abort on the first bad row.
+    #[default]
+    Strict,
+    /// Skip bad rows and record them in the `IngestReport`.
+    Lenient,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReadOptions {
+    pub mapping: ColumnMapping,
+    pub mode: ParseMode,
+    /// Rows that parse but fail this filter are dropped without error.
+    pub filter: RecordFilter,
+}
+
+impl ReadOptions {
+    pub fn lenient() -> Self {
+        Self {
+            mode: ParseMode::Lenient,
+            ..Self::default()
+        }
+    }
+}
+
+/// Rows dropped during a lenient read, and why.
+#[derive(Debug, Default)]
+pub struct IngestReport {
+    pub rows_read: usize,
+    pub skipped: Vec<IngestError>,
+    /// Valid rows left out by `ReadOptions::filter`.
+    pub filtered_out: usize,
+}
+
+impl IngestReport {
+    /// Number of skipped rows per error kind, sorted by kind.
+    pub fn skipped_by_kind(&self) -> BTreeMap<&'static str, usize> {
+        let mut counts = BTreeMap::new();
+        for err in &self.skipped {
+            *counts.entry(err.kind()).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+impl fmt::Display for IngestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Read {} rows, skipped {}",
+            self.rows_read,
+            self.skipped.len()
+        )?;
+        if self.filtered_out > 0 {
+            write!(f, ", filtered out {}", self.filtered_out)?;
+        }
+        for (kind, count) in self.skipped_by_kind() {
+            write!(f, "\n  {}: {}", kind, count)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct Ingest {
+    pub flights: Vec<FlightData>,
+    pub report: IngestReport,
+}
+
+pub fn read_csv(file_path: &str) -> Result<Vec<FlightData>, IngestError> {
+    Ok(read_csv_with(file_path, &ReadOptions::default())?.flights)
+}
+
+pub fn read_csv_with(file_path: &str, options: &ReadOptions) -> Result<Ingest, IngestError> {
+    let rdr = ReaderBuilder::new().flexible(true).from_path(file_path)?;
+    read_records(rdr, options)
+}
+
+/// Reads flight records from any CSV source, e.g. an in-memory buffer.
+pub fn read_csv_from_reader<R: io::Read>(
+    reader: R,
+    options: &ReadOptions,
+) -> Result<Ingest, IngestError> {
+    read_records(
+        ReaderBuilder::new().flexible(true).from_reader(reader),
+        options,
+    )
+}
+
+fn read_records<R: io::Read>(
+    mut rdr: Reader<R>,
+    options: &ReadOptions,
+) -> Result<Ingest, IngestError> {
+    let columns = ColumnIndices::resolve(rdr.headers()?, &options.mapping)?;
+    let mut flights = Vec::new();
+    let mut report = IngestReport::default();
+
+    for result in rdr.records() {
+        let parsed = result
+            .map_err(row_error)
+            .and_then(|record| parse_record(&record, &columns, &options.mapping));
+        report.rows_read += 1;
+
+        match parsed {
+            Ok(flight) if options.filter.matches(&flight) => flights.push(flight),
+            Ok(_) => report.filtered_out += 1,
+            Err(err @ IngestError::Csv(_)) => return Err(err),
+            Err(err) if options.mode == ParseMode::Lenient => report.skipped.push(err),
+            Err(err) => return Err(err),
+        }
+    }
+
+    Ok(Ingest { flights, report })
+}
+
+/// Locates a CSV reader error at its row when it concerns a single row, so
+/// lenient reads can skip it. I/O errors stay fatal.
+fn row_error(err: csv::Error) -> IngestError {
+    match err.position() {
+        Some(pos) if !err.is_io_error() => IngestError::UnreadableRow {
+            line: pos.line(),
+            source: err,
+        },
+        _ => IngestError::Csv(err),
+    }
+}
+
+fn parse_record(
+    record: &StringRecord,
+    columns: &ColumnIndices,
+    mapping: &ColumnMapping,
+) -> Result<FlightData, IngestError> {
+    let line = record.position().map_or(0, |pos| pos.line());
+    let field = |index: usize, column: &str| -> Result<&str, IngestError> {
+        match record.get(index).map(str::trim) {
+            Some(value) if !value.is_empty() => Ok(value),
+            _ => Err(IngestError::MissingField {
+                line,
+                column: column.to_string(),
+            }),
+        }
+    };
+    let number = |index: usize, column: &str| -> Result<u32, IngestError> {
+        let value = field(index, column)?;
+        value.parse().map_err(|_| IngestError::BadNumber {
+            line,
+            column: column.to_string(),
+            value: value.to_string(),
+        })
+    };
+
+    let month = number(columns.month, &mapping.month)?;
+    if !(1..=12).contains(&month) {
+        return Err(IngestError::BadDate {
+            line,
+            column: mapping.month.clone(),
+            value: month.to_string(),
+        });
+    }
+
+    let date = field(columns.date, &mapping.date)?;
+    let date: YearMonth = date.parse().map_err(|_| IngestError::BadDate {
+        line,
+        column: mapping.date.clone(),
+        value: date.to_string(),
+    })?;
+    // Filters use the year and month, period analyses the date
+    let year = number(columns.year, &mapping.year)?;
+    if (date.year, date.month) != (year, month) {
+        return Err(IngestError::DateMismatch {
+            line,
+            date,
+            year,
+            month,
+        });
+    }
+
+    let carrier_group = field(columns.carrier_group, &mapping.carrier_group)?;
+    let carrier_group = carrier_group
+        .parse()
+        .map_err(|_| IngestError::UnknownCarrierGroup {
+            line,
+            column: mapping.carrier_group.clone(),
+            value: carrier_group.to_string(),
+        })?;
+
+    let flight_type = field(columns.flight_type, &mapping.flight_type)?;
+    let flight_type = flight_type.parse().map_err(|_| IngestError::UnknownType {
+        line,
+        column: mapping.flight_type.clone(),
+        value: flight_type.to_string(),
+    })?;
+
+    Ok(FlightData {
+        date,
+        year,
+        month,
+        us_airport_id: number(columns.us_airport_id, &mapping.us_airport_id)?,
+        us_airport: field(columns.us_airport, &mapping.us_airport)?.to_string(),
+        us_wac: number(columns.us_wac, &mapping.us_wac)?,
+        foreign_airport_id: number(columns.foreign_airport_id, &mapping.foreign_airport_id)?,
+        foreign_airport: field(columns.foreign_airport, &mapping.foreign_airport)?.to_string(),
+        foreign_wac: number(columns.foreign_wac, &mapping.foreign_wac)?,
+        airline_id: number(columns.airline_id, &mapping.airline_id)?,
+        carrier: field(columns.carrier, &mapping.carrier)?.to_string(),
+        carrier_group,
+        flight_type,
+        scheduled_flights: number(columns.scheduled_flights, &mapping.scheduled_flights)?,
+        charter_flights: number(columns.charter_flights, &mapping.charter_flights)?,
+        total_flights: number(columns.total_flights, &mapping.total_flights)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+data_dte,Year,Month,usg_apt_id,usg_apt,usg_wac,fg_apt_id,fg_apt,fg_wac,airlineid,carrier,carriergroup,type,Scheduled,Charter,Total
+05/01/2006,2006,5,12016,GUM,5,13162,MAJ,844,20177,PFQ,1,Departures,0,10,10
+05/01/2003,2003,5,10299,ANC,1,13856,OKO,736,20007,5Y,1,Departures,0,15,15
+";
+
+    #[test]
+    fn test_read_csv_default_mapping() {
+        let flights = read_csv_from_reader(SAMPLE.as_bytes(), &ReadOptions::default())
+            .unwrap()
+            .flights;
+
+        assert_eq!(flights.len(), 2);
+        assert_eq!(flights[0].year, 2006);
+        assert_eq!(flights[0].us_airport, "GUM");
+        assert_eq!(flights[0].foreign_airport, "MAJ");
+        assert_eq!(flights[1].carrier, "5Y");
+        assert_eq!(flights[1].total_flights, 15);
+    }
+
+    #[test]
+    fn test_read_csv_all_columns() {
+        let flights = read_csv_from_reader(SAMPLE.as_bytes(), &ReadOptions::default())
+            .unwrap()
+            .flights;
+        let flight = &flights[0];
+
+        assert_eq!(flight.date, YearMonth::new(2006, 5).unwrap());
+        assert_eq!(flight.us_airport_id, 12016);
+        assert_eq!(flight.us_wac, 5);
+        assert_eq!(flight.foreign_airport_id, 13162);
+        assert_eq!(flight.foreign_wac, 844);
+        assert_eq!(flight.airline_id, 20177);
+        assert_eq!(flight.carrier_group, CarrierGroup::Us);
+        assert_eq!(flight.flight_type, FlightType::Departures);
+        assert_eq!(flight.scheduled_flights, 0);
+        assert_eq!(flight.charter_flights, 10);
+    }
+
+    #[test]
+    fn test_parse_year_month() {
+        assert_eq!("05/01/2006".parse(), Ok(YearMonth::new(2006, 5).unwrap()));
+        assert_eq!("2019-12".parse(), Ok(YearMonth::new(2019, 12).unwrap()));
+        assert_eq!("2019-12-01".parse(), Ok(YearMonth::new(2019, 12).unwrap()));
+        assert!("13/01/2006".parse::<YearMonth>().is_err());
+        assert!("May 2006".parse::<YearMonth>().is_err());
+        assert_eq!(YearMonth::new(2006, 5).unwrap().to_string(), "2006-05");
+    }
+
+    #[test]
+    fn test_read_csv_reordered_columns() {
+        let csv = "\
+Total,carrier,fg_apt,extra,usg_apt,type,Month,Year,Charter,Scheduled,carriergroup,airlineid,fg_wac,fg_apt_id,usg_wac,usg_apt_id,data_dte
+42,AA,LHR,x,JFK,Departures,7,2019,2,40,0,19805,493,13296,22,12478,07/01/2019
+";
+        let flights = read_csv_from_reader(csv.as_bytes(), &ReadOptions::default())
+            .unwrap()
+            .flights;
+
+        assert_eq!(flights[0].year, 2019);
+        assert_eq!(flights[0].month, 7);
+        assert_eq!(flights[0].us_airport, "JFK");
+        assert_eq!(flights[0].foreign_airport, "LHR");
+        assert_eq!(flights[0].carrier, "AA");
+        assert_eq!(flights[0].carrier_group, CarrierGroup::Foreign);
+        assert_eq!(flights[0].scheduled_flights, 40);
+        assert_eq!(flights[0].total_flights, 42);
+    }
+
+    #[test]
+    fn test_read_csv_missing_headers() {
+        let csv = "\
+data_dte,Year,Month,usg_apt_id,usg_apt,usg_wac,fg_apt_id,fg_wac,airlineid,carrier,carriergroup,type,Scheduled,Charter
+07/01/2019,2019,7,12478,JFK,22,13296,493,19805,AA,1,Departures,40,2
+";
+        let err = read_csv_from_reader(csv.as_bytes(), &ReadOptions::default()).unwrap_err();
+
+        match err {
+            IngestError::MissingHeaders(missing) => {
+                assert_eq!(missing, vec!["fg_apt".to_string(), "Total".to_string()])
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+
+    #[test]
+    fn test_read_csv_custom_mapping() {
+        let csv = "\
+period,yr,mo,origin_id,origin,origin_wac,dest_id,dest,dest_wac,airline_id,airline,group,kind,sched,chart,flights
+2019-07,2019,7,12478,JFK,22,13296,LHR,493,19805,AA,1,Departures,3,0,3
+";
+        let mapping = ColumnMapping {
+            date: "period".to_string(),
+            year: "yr".to_string(),
+            month: "mo".to_string(),
+            us_airport_id: "origin_id".to_string(),
+            us_airport: "origin".to_string(),
+            us_wac: "origin_wac".to_string(),
+            foreign_airport_id: "dest_id".to_string(),
+            foreign_airport: "dest".to_string(),
+            foreign_wac: "dest_wac".to_string(),
+            airline_id: "airline_id".to_string(),
+            carrier: "airline".to_string(),
+            carrier_group: "group".to_string(),
+            flight_type: "kind".to_string(),
+            scheduled_flights: "sched".to_string(),
+            charter_flights: "chart".to_string(),
+            total_flights: "flights".to_string(),
+        };
+        let options = ReadOptions {
+            mapping,
+            ..ReadOptions::default()
+        };
+        let flights = read_csv_from_reader(csv.as_bytes(), &options)
+            .unwrap()
+            .flights;
+
+        assert_eq!(flights[0].foreign_airport, "LHR");
+        assert_eq!(flights[0].total_flights, 3);
+    }
+
+    const MESSY: &str = "\
+data_dte,Year,Month,usg_apt_id,usg_apt,usg_wac,fg_apt_id,fg_apt,fg_wac,airlineid,carrier,carriergroup,type,Scheduled,Charter,Total
+07/01/2019,2019,7,12478,JFK,22,13296,LHR,493,19805,AA,1,Departures,42,0,42
+07/01/2019,2019,7,12478,JFK,22,11045,CDG,427,19704,AF,0,Departures,5,0,abc
+13/01/2019,2019,13,10721,BOS,13,11214,DUB,450,20437,EI,0,Departures,5,0,5
+08/01/2019,2019,8,12892,,91,13789,NRT,736,20016,JL,0,Departures,5,0,5
+08/01/2019,2019,8,12892,LAX,91,15047,SYD,802,20071,QF,0,Returns,5,0,5
+08/01/2019,2019,8,12892,LAX,91,15047,SYD,802,20071,QF,2,Departures,5,0,5
+09/01/2019,2019,9,14771,SFO,91,12173,HKG,781,20120,CX,0,Departures,7,0,7
+";
+
+    #[test]
+    fn test_read_csv_strict_reports_row_and_column() {
+        let err = read_csv_from_reader(MESSY.as_bytes(), &ReadOptions::default()).unwrap_err();
+
+        match err {
+            IngestError::BadNumber {
+                line,
+                column,
+                value,
+            } => {
+                assert_eq!(line, 3);
+                assert_eq!(column, "Total");
+                assert_eq!(value, "abc");
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+
+    #[test]
+    fn test_read_csv_lenient_skips_bad_rows() {
+        let ingest = read_csv_from_reader(MESSY.as_bytes(), &ReadOptions::lenient()).unwrap();
+
+        assert_eq!(ingest.flights.len(), 2);
+        assert_eq!(ingest.report.rows_read, 7);
+        assert_eq!(ingest.report.skipped.len(), 5);
+
+        let by_kind = ingest.report.skipped_by_kind();
+        assert_eq!(by_kind["bad number"], 1);
+        assert_eq!(by_kind["bad date"], 1);
+        assert_eq!(by_kind["missing field"], 1);
+        assert_eq!(by_kind["unknown type"], 1);
+        assert_eq!(by_kind["unknown carrier group"], 1);
+        assert!(matches!(
+            ingest.report.skipped[2],
+            IngestError::MissingField { line: 5, .. }
+        ));
+    }
+
+    #[test]
+    fn test_read_csv_rejects_date_mismatch() {
+        let csv = format!(
+            "{}08/01/2019,2019,7,12892,LAX,91,15047,SYD,802,20071,QF,0,Departures,5,0,5\n",
+            SAMPLE
+        );
+
+        let err = read_csv_from_reader(csv.as_bytes(), &ReadOptions::default()).unwrap_err();
+        assert!(matches!(
+            err,
+            IngestError::DateMismatch {
+                line: 4,
+                year: 2019,
+                month: 7,
+                ..
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "line 4: date 2019-08 does not match year 2019 and month 7"
+        );
+
+        let ingest = read_csv_from_reader(csv.as_bytes(), &ReadOptions::lenient()).unwrap();
+        assert_eq!(ingest.flights.len(), 2);
+        assert_eq!(ingest.report.skipped_by_kind()["date mismatch"], 1);
+    }
+
+    #[test]
+    fn test_read_csv_lenient_skips_invalid_utf8() {
+        let mut csv = SAMPLE.as_bytes().to_vec();
+        csv.extend_from_slice(
+            b"08/01/2019,2019,8,12892,LAX,91,15047,S\xffD,802,20071,QF,0,Departures,5,0,5\n",
+        );
+
+        let ingest = read_csv_from_reader(csv.as_slice(), &ReadOptions::lenient()).unwrap();
+        assert_eq!(ingest.flights.len(), 2);
+        assert_eq!(ingest.report.skipped_by_kind()["unreadable row"], 1);
+        assert!(matches!(
+            ingest.report.skipped[0],
+            IngestError::UnreadableRow { line: 4, .. }
+        ));
+
+        let err = read_csv_from_reader(csv.as_slice(), &ReadOptions::default()).unwrap_err();
+        assert!(err.to_string().starts_with("line 4: unreadable row"));
+    }
+
+    #[test]
+    fn test_read_csv_with_filter() {
+        let options = ReadOptions {
+            filter: RecordFilter::new().us_airports(["ANC"]),
+            ..ReadOptions::default()
+        };
+        let ingest = read_csv_from_reader(SAMPLE.as_bytes(), &options).unwrap();
+
+        assert_eq!(ingest.flights.len(), 1);
+        assert_eq!(ingest.flights[0].us_airport, "ANC");
+        assert_eq!(ingest.report.filtered_out, 1);
+        assert!(ingest.report.skipped.is_empty());
+    }
+}
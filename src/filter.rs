@@ -0,0 +1,207 @@
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+
+use crate::data::{CarrierGroup, FlightData, ServiceClass, YearMonth};
+
+/// Criteria for selecting flight records, built up one restriction at a time:
+///
+/// ```
+/// use final_project::data::CarrierGroup;
+/// use final_project::filter::RecordFilter;
+///
+/// let filter = RecordFilter::new()
+///     .years(2019..=2019)
+///     .carrier_group(CarrierGroup::Us);
+/// ```
+///
+/// An unset criterion lets every record through. Can be applied while
+/// reading the CSV (`ReadOptions::filter`) or to records already loaded.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecordFilter {
+    pub years: Option<RangeInclusive<u32>>,
+    /// Months of the year, e.g. `6..=8` for every summer.
+    pub months: Option<RangeInclusive<u32>>,
+    /// A continuous span of calendar months.
+    pub period: Option<RangeInclusive<YearMonth>>,
+    pub carriers: Option<HashSet<String>>,
+    pub excluded_carriers: HashSet<String>,
+    pub us_airports: Option<HashSet<String>>,
+    pub foreign_airports: Option<HashSet<String>>,
+    pub carrier_group: Option<CarrierGroup>,
+    /// Minimum number of flights of a class of service on the record.
+    pub min_flights: Option<(ServiceClass, u32)>,
+}
+
+fn to_set<I, S>(codes: I) -> HashSet<String>
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    codes.into_iter().map(Into::into).collect()
+}
+
+impl RecordFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn years(mut self, years: RangeInclusive<u32>) -> Self {
+        self.years = Some(years);
+        self
+    }
+
+    pub fn months(mut self, months: RangeInclusive<u32>) -> Self {
+        self.months = Some(months);
+        self
+    }
+
+    pub fn period(mut self, period: RangeInclusive<YearMonth>) -> Self {
+        self.period = Some(period);
+        self
+    }
+
+    /// Only keep these carriers. Calling again adds to the list.
+    pub fn carriers<I, S>(mut self, carriers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.carriers
+            .get_or_insert_with(HashSet::new)
+            .extend(to_set(carriers));
+        self
+    }
+
+    pub fn exclude_carriers<I, S>(mut self, carriers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.excluded_carriers.extend(to_set(carriers));
+        self
+    }
+
+    /// Only keep departures from these US gateways.
+    pub fn us_airports<I, S>(mut self, airports: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.us_airports
+            .get_or_insert_with(HashSet::new)
+            .extend(to_set(airports));
+        self
+    }
+
+    /// Only keep flights to these foreign airports.
+    pub fn foreign_airports<I, S>(mut self, airports: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.foreign_airports
+            .get_or_insert_with(HashSet::new)
+            .extend(to_set(airports));
+        self
+    }
+
+    pub fn carrier_group(mut self, group: CarrierGroup) -> Self {
+        self.carrier_group = Some(group);
+        self
+    }
+
+    pub fn min_flights(mut self, class: ServiceClass, flights: u32) -> Self {
+        self.min_flights = Some((class, flights));
+        self
+    }
+
+    pub fn matches(&self, flight: &FlightData) -> bool {
+        let within = |range: &Option<RangeInclusive<u32>>, value: u32| {
+            range.as_ref().is_none_or(|r| r.contains(&value))
+        };
+        let listed = |set: &Option<HashSet<String>>, code: &str| {
+            set.as_ref().is_none_or(|s| s.contains(code))
+        };
+
+        within(&self.years, flight.year)
+            && within(&self.months, flight.month)
+            && self
+                .period
+                .as_ref()
+                .is_none_or(|p| p.contains(&flight.date))
+            && listed(&self.carriers, &flight.carrier)
+            && !self.excluded_carriers.contains(&flight.carrier)
+            && listed(&self.us_airports, &flight.us_airport)
+            && listed(&self.foreign_airports, &flight.foreign_airport)
+            && self
+                .carrier_group
+                .is_none_or(|group| group == flight.carrier_group)
+            && self
+                .min_flights
+                .is_none_or(|(class, min)| flight.flights(class) >= min)
+    }
+
+    /// Copies of the records that pass the filter.
+    pub fn apply(&self, flights: &[FlightData]) -> Vec<FlightData> {
+        flights
+            .iter()
+            .filter(|f| self.matches(f))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<FlightData> {
+        let mut foreign = FlightData::for_test(2019, 7, "JFK", "LHR", "BA", 30, 0);
+        foreign.carrier_group = CarrierGroup::Foreign;
+        vec![
+            FlightData::for_test(2019, 1, "JFK", "LHR", "AA", 50, 0),
+            foreign,
+            FlightData::for_test(2018, 12, "BOS", "DUB", "AA", 4, 0),
+            FlightData::for_test(2019, 8, "GUM", "MAJ", "PFQ", 0, 10),
+        ]
+    }
+
+    fn carriers(flights: &[FlightData]) -> Vec<&str> {
+        flights.iter().map(|f| f.carrier.as_str()).collect()
+    }
+
+    #[test]
+    fn test_default_filter_keeps_everything() {
+        assert_eq!(RecordFilter::new().apply(&sample()).len(), 4);
+    }
+
+    #[test]
+    fn test_filter_criteria_compose() {
+        let flights = sample();
+
+        let us_2019 = RecordFilter::new()
+            .years(2019..=2019)
+            .carrier_group(CarrierGroup::Us);
+        assert_eq!(carriers(&us_2019.apply(&flights)), vec!["AA", "PFQ"]);
+
+        let summer = RecordFilter::new().months(6..=8);
+        assert_eq!(carriers(&summer.apply(&flights)), vec!["BA", "PFQ"]);
+
+        let period = RecordFilter::new()
+            .period(YearMonth::new(2018, 12).unwrap()..=YearMonth::new(2019, 1).unwrap());
+        assert_eq!(period.apply(&flights).len(), 2);
+
+        let jfk = RecordFilter::new()
+            .us_airports(["JFK", "BOS"])
+            .exclude_carriers(["BA"]);
+        assert_eq!(carriers(&jfk.apply(&flights)), vec!["AA", "AA"]);
+
+        let lhr_aa = RecordFilter::new()
+            .foreign_airports(["LHR"])
+            .carriers(["AA"]);
+        assert_eq!(lhr_aa.apply(&flights).len(), 1);
+
+        let busy = RecordFilter::new().min_flights(ServiceClass::Scheduled, 5);
+        assert_eq!(carriers(&busy.apply(&flights)), vec!["AA", "BA"]);
+    }
+}
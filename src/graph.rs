@@ -0,0 +1,679 @@
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::OnceLock;
+
+use crate::data::{FlightData, FlightType, ServiceClass, YearMonth};
+use crate::ranking::top_k;
+
+mod betweenness;
+mod bipartite;
+mod centrality;
+mod community;
+mod csr;
+mod cuts;
+mod interner;
+mod multiplex;
+mod parallel;
+mod paths;
+mod spectral;
+
+pub use betweenness::BetweennessOptions;
+pub use bipartite::{NodeRole, ProjectionWeight};
+pub use centrality::{DistanceCentrality, DistanceSummary};
+pub use community::{Communities, CommunitySummary, LouvainOptions};
+pub use csr::Csr;
+pub use cuts::{ArticulationPoint, Bridge};
+pub use interner::{Interner, NodeId};
+pub use multiplex::{build_multiplex, LayerBy, LayerOverlap, LayerSummary, Multiplex};
+pub use parallel::Progress;
+pub use paths::{EdgeCost, ShortestPaths, UnsupportedCost};
+pub use spectral::{EigenvectorOptions, IterativeScores, PageRankOptions};
+
+/// Distance reported by the integer BFS for nodes it could not reach.
+pub const UNREACHABLE: u32 = u32::MAX;
+
+/// Airport graph with one aggregated edge per airport pair.
+///
+/// Airports are interned to dense `NodeId`s. Edges are accumulated in a map
+/// while the graph is built, and a CSR adjacency is derived from them on
+/// first use by any traversal, so algorithms never hash airport codes.
+///
+/// Graphs are undirected by default. A directed graph keeps each direction
+/// of a route as its own edge (US gateway to foreign airport for the
+/// departures extract), and traversals follow outgoing edges.
+#[derive(Debug, Default)]
+pub struct Graph {
+    nodes: Interner,
+    directed: bool,
+    edges: HashMap<(NodeId, NodeId), u64>, // Route (lower ID first unless directed) -> Total weight
+    breakdowns: HashMap<(NodeId, NodeId), EdgeBreakdown>, // Route -> flights by month/carrier
+    csr: OnceLock<Csr>,
+    in_csr: OnceLock<Csr>, // Incoming arcs, only built for directed graphs
+    roles: Vec<Option<NodeRole>>, // Indexed by NodeId, filled in by add_flight
+}
+
+/// Which per-edge breakdowns `build_graph_with` should record.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Breakdowns {
+    pub by_month: bool,
+    pub by_carrier: bool,
+}
+
+/// Flights on one route split by month and by carrier.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EdgeBreakdown {
+    pub by_month: BTreeMap<YearMonth, u32>,
+    pub by_carrier: BTreeMap<String, u32>,
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn new_directed() -> Self {
+        Self {
+            directed: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// Key for a route: as given when directed, otherwise endpoints in ID order.
+    fn route_key(&self, from: NodeId, to: NodeId) -> (NodeId, NodeId) {
+        if self.directed {
+            (from, to)
+        } else {
+            (from.min(to), from.max(to))
+        }
+    }
+
+    /// Adds an airport without any edges, returning its ID.
+    pub fn add_node(&mut self, code: &str) -> NodeId {
+        let count = self.nodes.len();
+        let id = self.nodes.intern(code);
+        if self.nodes.len() != count {
+            self.csr.take();
+            self.in_csr.take();
+        }
+        id
+    }
+
+    /// Adds `weight` flights between two airports, summing into any existing edge.
+    pub fn add_edge(&mut self, from: &str, to: &str, weight: u64) {
+        let from = self.nodes.intern(from);
+        let to = self.nodes.intern(to);
+        let key = self.route_key(from, to);
+        *self.edges.entry(key).or_insert(0) += weight;
+        // Adjacency is rebuilt on next use
+        self.csr.take();
+        self.in_csr.take();
+    }
+
+    /// Adds a flight row's count for `class`, recording the requested breakdowns.
+    ///
+    /// In a directed graph, departures rows point from the US airport to the
+    /// foreign one and arrivals rows the other way.
+    pub fn add_flight(&mut self, flight: &FlightData, class: ServiceClass, breakdowns: Breakdowns) {
+        let count = flight.flights(class);
+        if count == 0 {
+            return;
+        }
+
+        let (from, to) = match flight.flight_type {
+            FlightType::Departures => (&flight.us_airport, &flight.foreign_airport),
+            FlightType::Arrivals => (&flight.foreign_airport, &flight.us_airport),
+        };
+        self.add_edge(from, to, count as u64);
+        self.set_role(&flight.us_airport, NodeRole::UsGateway);
+        self.set_role(&flight.foreign_airport, NodeRole::Foreign);
+
+        if breakdowns.by_month || breakdowns.by_carrier {
+            let from = self.nodes.intern(from);
+            let to = self.nodes.intern(to);
+            let key = self.route_key(from, to);
+            let breakdown = self.breakdowns.entry(key).or_default();
+            if breakdowns.by_month {
+                *breakdown.by_month.entry(flight.date).or_insert(0) += count;
+            }
+            if breakdowns.by_carrier {
+                *breakdown
+                    .by_carrier
+                    .entry(flight.carrier.clone())
+                    .or_insert(0) += count;
+            }
+        }
+    }
+
+    /// CSR adjacency over node IDs, built once after the last edge was added.
+    ///
+    /// For directed graphs this holds outgoing edges only.
+    pub fn adjacency(&self) -> &Csr {
+        self.csr.get_or_init(|| {
+            let mut arcs = Vec::with_capacity(self.edges.len() * 2);
+            for (&(a, b), &weight) in &self.edges {
+                arcs.push((a, b, weight));
+                if a != b && !self.directed {
+                    arcs.push((b, a, weight));
+                }
+            }
+            Csr::from_arcs(self.nodes.len(), &arcs)
+        })
+    }
+
+    /// Incoming edges of each node; the same as `adjacency` when undirected.
+    pub fn in_adjacency(&self) -> &Csr {
+        if !self.directed {
+            return self.adjacency();
+        }
+        self.in_csr.get_or_init(|| {
+            let arcs: Vec<_> = self
+                .edges
+                .iter()
+                .map(|(&(a, b), &weight)| (b, a, weight))
+                .collect();
+            Csr::from_arcs(self.nodes.len(), &arcs)
+        })
+    }
+
+    pub fn interner(&self) -> &Interner {
+        &self.nodes
+    }
+
+    pub fn node_id(&self, code: &str) -> Option<NodeId> {
+        self.nodes.get(code)
+    }
+
+    pub fn node_code(&self, id: NodeId) -> &str {
+        self.nodes.code(id)
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Number of edges touching `node`, counting both directions when directed.
+    pub fn degree(&self, node: &str) -> usize {
+        if self.directed {
+            self.out_degree(node) + self.in_degree(node)
+        } else {
+            self.out_degree(node)
+        }
+    }
+
+    pub fn out_degree(&self, node: &str) -> usize {
+        self.node_id(node)
+            .map_or(0, |id| self.adjacency().degree(id))
+    }
+
+    pub fn in_degree(&self, node: &str) -> usize {
+        self.node_id(node)
+            .map_or(0, |id| self.in_adjacency().degree(id))
+    }
+
+    /// Total flights from `a` to `b` (in either direction if undirected).
+    pub fn edge_weight(&self, a: &str, b: &str) -> Option<u64> {
+        let key = self.route_key(self.node_id(a)?, self.node_id(b)?);
+        self.edges.get(&key).copied()
+    }
+
+    /// Per-month/per-carrier flights on a route, if recorded at build time.
+    pub fn edge_breakdown(&self, a: &str, b: &str) -> Option<&EdgeBreakdown> {
+        let key = self.route_key(self.node_id(a)?, self.node_id(b)?);
+        self.breakdowns.get(&key)
+    }
+
+    /// Hop distances from `start` to every node, indexed by `NodeId`,
+    /// following outgoing edges. Nodes that cannot be reached are set to
+    /// `UNREACHABLE`.
+    pub fn bfs_distances(&self, start: NodeId) -> Vec<u32> {
+        let mut distances = vec![UNREACHABLE; self.node_count()];
+        let mut queue = VecDeque::new();
+        self.bfs_into(self.adjacency(), start, &mut distances, &mut queue);
+        distances
+    }
+
+    /// Hop distances from every node to `target`, following edges in their
+    /// direction. Same as `bfs_distances` for undirected graphs.
+    pub fn bfs_distances_to(&self, target: NodeId) -> Vec<u32> {
+        let mut distances = vec![UNREACHABLE; self.node_count()];
+        let mut queue = VecDeque::new();
+        self.bfs_into(self.in_adjacency(), target, &mut distances, &mut queue);
+        distances
+    }
+
+    /// BFS over `csr` that reuses caller-owned buffers, so all-sources
+    /// algorithms do not allocate per source. `distances` must be all
+    /// `UNREACHABLE` on entry.
+    fn bfs_into(
+        &self,
+        csr: &Csr,
+        start: NodeId,
+        distances: &mut [u32],
+        queue: &mut VecDeque<NodeId>,
+    ) {
+        distances[start as usize] = 0;
+        queue.push_back(start);
+
+        while let Some(current) = queue.pop_front() {
+            let next = distances[current as usize] + 1;
+            for &neighbor in csr.neighbors(current) {
+                if distances[neighbor as usize] == UNREACHABLE {
+                    distances[neighbor as usize] = next;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+    }
+
+    pub fn bfs_shortest_paths(&self, start: &str) -> HashMap<String, u32> {
+        let Some(start) = self.node_id(start) else {
+            return HashMap::new();
+        };
+
+        self.bfs_distances(start)
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, distance)| distance != UNREACHABLE)
+            .map(|(id, distance)| (self.node_code(id as NodeId).to_string(), distance))
+            .collect()
+    }
+
+    /// Connected components as lists of node IDs. Directed graphs are split
+    /// into weakly connected components, ignoring edge direction.
+    pub fn component_ids(&self) -> Vec<Vec<NodeId>> {
+        let (out, incoming) = (self.adjacency(), self.in_adjacency());
+        let mut visited = vec![false; self.node_count()];
+        let mut components = Vec::new();
+
+        for node in 0..self.node_count() as NodeId {
+            if !visited[node as usize] {
+                let mut component = Vec::new();
+                let mut stack = vec![node];
+                visited[node as usize] = true;
+                while let Some(current) = stack.pop() {
+                    component.push(current);
+                    let neighbors = out.neighbors(current).iter();
+                    let reverse = if self.directed {
+                        incoming.neighbors(current)
+                    } else {
+                        &[]
+                    };
+                    for &neighbor in neighbors.chain(reverse) {
+                        if !visited[neighbor as usize] {
+                            visited[neighbor as usize] = true;
+                            stack.push(neighbor);
+                        }
+                    }
+                }
+                components.push(component);
+            }
+        }
+
+        components
+    }
+
+    pub fn connected_components(&self) -> Vec<HashSet<String>> {
+        self.component_ids()
+            .into_iter()
+            .map(|component| {
+                component
+                    .into_iter()
+                    .map(|id| self.node_code(id).to_string())
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn largest_component(&self) -> HashSet<String> {
+        self.connected_components()
+            .into_iter()
+            .max_by_key(|component| component.len())
+            .unwrap_or_default()
+    }
+
+    pub fn busiest_routes(&self) -> Vec<((String, String), u64)> {
+        self.routes().into_iter().take(5).collect()
+    }
+
+    /// Every route with its total flights, busiest first.
+    pub fn routes(&self) -> Vec<((String, String), u64)> {
+        let mut routes: Vec<_> = self
+            .edges
+            .iter()
+            .map(|(&(a, b), &weight)| {
+                let (a, b) = (self.node_code(a), self.node_code(b));
+                // Report undirected routes with their airport codes in alphabetical order
+                let route = if a <= b || self.directed {
+                    (a, b)
+                } else {
+                    (b, a)
+                };
+                ((route.0.to_string(), route.1.to_string()), weight)
+            })
+            .collect();
+
+        routes.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0))); // Sort by total flights in descending order
+        routes
+    }
+}
+
+/// Builds the airport graph from the flights of one class of service.
+///
+/// Rows with no flights in that class are left out, so e.g. a charter graph
+/// only contains routes that actually saw charter service.
+pub fn build_graph(flights: &[FlightData], class: ServiceClass) -> Graph {
+    build_graph_with(flights, class, Breakdowns::default())
+}
+
+/// Like `build_graph`, but with directed edges following the direction of
+/// travel: US to foreign airport for departures rows, foreign to US airport
+/// for arrivals rows.
+pub fn build_directed_graph(flights: &[FlightData], class: ServiceClass) -> Graph {
+    let mut graph = Graph::new_directed();
+
+    for flight in flights {
+        graph.add_flight(flight, class, Breakdowns::default());
+    }
+
+    graph
+}
+
+/// Like `build_graph`, additionally recording per-route breakdowns.
+pub fn build_graph_with(
+    flights: &[FlightData],
+    class: ServiceClass,
+    breakdowns: Breakdowns,
+) -> Graph {
+    let mut graph = Graph::new();
+
+    for flight in flights {
+        graph.add_flight(flight, class, breakdowns);
+    }
+
+    graph
+}
+
+pub fn top_busiest_airports(flights: &[FlightData], class: ServiceClass) -> Vec<(String, u32)> {
+    top_k(&airport_totals(flights, class), 5, |_| true)
+}
+
+/// Flights departing each US gateway for the given class of service.
+pub fn airport_totals(flights: &[FlightData], class: ServiceClass) -> HashMap<String, u32> {
+    let mut airport_totals: HashMap<String, u32> = HashMap::new();
+
+    for flight in flights {
+        let count = flight.flights(class);
+        if count > 0 {
+            *airport_totals.entry(flight.us_airport.clone()).or_insert(0) += count;
+        }
+    }
+
+    airport_totals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph_add_edge() {
+        let mut graph = Graph::new();
+        graph.add_edge("A", "B", 10);
+        graph.add_edge("B", "C", 5);
+
+        assert_eq!(graph.node_count(), 3); // Nodes: A, B, C
+        assert_eq!(graph.degree("A"), 1); // A -> B
+        assert_eq!(graph.degree("B"), 2); // B -> A, B -> C
+        assert_eq!(graph.degree("C"), 1); // C -> B
+    }
+
+    #[test]
+    fn test_graph_bfs_shortest_paths() {
+        let mut graph = Graph::new();
+        graph.add_edge("A", "B", 1);
+        graph.add_edge("B", "C", 1);
+        graph.add_edge("A", "C", 2); // Edge weight ignored in BFS (unweighted graph)
+
+        let distances = graph.bfs_shortest_paths("A");
+
+        assert_eq!(distances["A"], 0); // Distance to self
+        assert_eq!(distances["B"], 1); // Distance from A -> B
+        assert_eq!(distances["C"], 1); // Distance from A -> C (direct edge)
+    }
+
+    #[test]
+    fn test_connected_components() {
+        let mut graph = Graph::new();
+        graph.add_edge("A", "B", 1);
+        graph.add_edge("B", "C", 1);
+        graph.add_edge("D", "E", 1); // Separate component
+
+        let components = graph.connected_components();
+
+        assert_eq!(components.len(), 2); // Two connected components
+        assert!(components
+            .iter()
+            .any(|c| c.contains("A") && c.contains("B") && c.contains("C"))); // Component 1
+        assert!(components
+            .iter()
+            .any(|c| c.contains("D") && c.contains("E"))); // Component 2
+    }
+
+    #[test]
+    fn test_largest_component() {
+        let mut graph = Graph::new();
+        graph.add_edge("A", "B", 1);
+        graph.add_edge("B", "C", 1);
+        graph.add_edge("D", "E", 1); // Separate smaller component
+
+        let largest_component = graph.largest_component();
+
+        assert_eq!(largest_component.len(), 3); // Largest component size
+        assert!(largest_component.contains("A"));
+        assert!(largest_component.contains("B"));
+        assert!(largest_component.contains("C"));
+    }
+
+    #[test]
+    fn test_harmonic_centrality() {
+        let mut graph = Graph::new();
+        graph.add_edge("A", "B", 1);
+        graph.add_edge("B", "C", 1);
+        graph.add_edge("C", "D", 1);
+
+        let harmonic_centralities = top_k(&graph.harmonic_centrality(), 5, |_| true);
+
+        // Validate the top node by centrality
+        assert_eq!(harmonic_centralities[0].0, "B"); // Node B is central
+        assert!(harmonic_centralities[0].1 > 0.0); // Centrality score > 0
+        assert_eq!(harmonic_centralities[1].0, "C"); // Ties broken by airport code
+        assert_eq!(harmonic_centralities.len(), 4); // All nodes returned, not just a fixed top 5
+    }
+
+    #[test]
+    fn test_busiest_routes() {
+        let mut graph = Graph::new();
+        graph.add_edge("A", "B", 100);
+        graph.add_edge("B", "C", 200);
+        graph.add_edge("C", "D", 50);
+
+        let busiest_routes = graph.busiest_routes();
+
+        // Check the top route
+        assert_eq!(busiest_routes[0].0, ("B".to_string(), "C".to_string())); // Top route
+        assert_eq!(busiest_routes[0].1, 200); // Flight count
+    }
+
+    fn sample_flights() -> Vec<FlightData> {
+        vec![
+            FlightData::for_test(2019, 1, "JFK", "LHR", "AA", 100, 0),
+            FlightData::for_test(2019, 1, "GUM", "MAJ", "PFQ", 0, 30),
+            FlightData::for_test(2019, 1, "ANC", "OKO", "5Y", 5, 20),
+        ]
+    }
+
+    #[test]
+    fn test_build_graph_service_class() {
+        let flights = sample_flights();
+
+        let scheduled = build_graph(&flights, ServiceClass::Scheduled);
+        assert_eq!(scheduled.node_count(), 4); // GUM-MAJ has no scheduled service
+        assert!(scheduled.node_id("GUM").is_none());
+
+        let charter = build_graph(&flights, ServiceClass::Charter);
+        assert_eq!(charter.node_count(), 4); // JFK-LHR has no charter service
+        assert_eq!(charter.edge_weight("ANC", "OKO"), Some(20));
+
+        let total = build_graph(&flights, ServiceClass::Total);
+        assert_eq!(total.node_count(), 6);
+        assert_eq!(total.edge_weight("OKO", "ANC"), Some(25));
+    }
+
+    #[test]
+    fn test_top_busiest_airports_service_class() {
+        let flights = sample_flights();
+
+        let charter = top_busiest_airports(&flights, ServiceClass::Charter);
+        assert_eq!(
+            charter,
+            vec![("GUM".to_string(), 30), ("ANC".to_string(), 20)]
+        );
+
+        let scheduled = top_busiest_airports(&flights, ServiceClass::Scheduled);
+        assert_eq!(scheduled[0], ("JFK".to_string(), 100));
+        assert_eq!(scheduled.len(), 2);
+    }
+
+    #[test]
+    fn test_add_edge_aggregates_parallel_edges() {
+        let mut graph = Graph::new();
+        graph.add_edge("JFK", "LHR", 10);
+        graph.add_edge("LHR", "JFK", 5);
+        graph.add_edge("JFK", "LHR", 20);
+        graph.add_edge("JFK", "CDG", 1);
+
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(graph.degree("JFK"), 2);
+        assert_eq!(graph.edge_weight("JFK", "LHR"), Some(35));
+        assert_eq!(graph.edge_weight("LHR", "JFK"), Some(35));
+        assert_eq!(graph.edge_weight("LHR", "CDG"), None);
+
+        // Totals past u32::MAX do not wrap
+        let count = u32::MAX as u64;
+        graph.add_edge("JFK", "CDG", count);
+        graph.add_edge("CDG", "JFK", count);
+        assert_eq!(graph.edge_weight("JFK", "CDG"), Some(2 * count + 1));
+    }
+
+    #[test]
+    fn test_build_graph_breakdowns() {
+        let flights = vec![
+            FlightData::for_test(2019, 1, "JFK", "LHR", "AA", 10, 0),
+            FlightData::for_test(2019, 1, "JFK", "LHR", "BA", 12, 0),
+            FlightData::for_test(2019, 2, "JFK", "LHR", "AA", 8, 1),
+        ];
+        let breakdowns = Breakdowns {
+            by_month: true,
+            by_carrier: true,
+        };
+        let graph = build_graph_with(&flights, ServiceClass::Total, breakdowns);
+        let breakdown = graph.edge_breakdown("LHR", "JFK").unwrap();
+
+        assert_eq!(graph.edge_count(), 1);
+        assert_eq!(graph.edge_weight("JFK", "LHR"), Some(31));
+        assert_eq!(breakdown.by_month[&YearMonth::new(2019, 1).unwrap()], 22);
+        assert_eq!(breakdown.by_month[&YearMonth::new(2019, 2).unwrap()], 9);
+        assert_eq!(breakdown.by_carrier["AA"], 19);
+        assert_eq!(breakdown.by_carrier["BA"], 12);
+
+        let plain = build_graph(&flights, ServiceClass::Total);
+        assert!(plain.edge_breakdown("JFK", "LHR").is_none());
+    }
+
+    #[test]
+    fn test_adjacency_rebuilt_after_add_edge() {
+        let mut graph = Graph::new();
+        graph.add_edge("A", "B", 1);
+        let a = graph.node_id("A").unwrap();
+        assert_eq!(graph.adjacency().degree(a), 1);
+
+        graph.add_edge("A", "C", 1);
+        assert_eq!(graph.adjacency().degree(a), 2);
+
+        let distances = graph.bfs_distances(graph.node_id("B").unwrap());
+        assert_eq!(distances[graph.node_id("C").unwrap() as usize], 2);
+
+        graph.add_edge("D", "E", 1);
+        let distances = graph.bfs_distances(a);
+        assert_eq!(distances[graph.node_id("D").unwrap() as usize], UNREACHABLE);
+    }
+
+    #[test]
+    fn test_directed_graph() {
+        let mut graph = Graph::new_directed();
+        graph.add_edge("JFK", "LHR", 10);
+        graph.add_edge("LHR", "JFK", 4);
+        graph.add_edge("JFK", "CDG", 1);
+        graph.add_edge("BOS", "CDG", 1);
+
+        assert_eq!(graph.edge_count(), 4);
+        assert_eq!(graph.edge_weight("JFK", "LHR"), Some(10));
+        assert_eq!(graph.edge_weight("LHR", "JFK"), Some(4));
+        assert_eq!(graph.edge_weight("CDG", "JFK"), None);
+        assert_eq!(graph.out_degree("JFK"), 2);
+        assert_eq!(graph.in_degree("JFK"), 1);
+        assert_eq!(graph.in_degree("CDG"), 2);
+        assert_eq!(graph.degree("CDG"), 2);
+
+        let distances = graph.bfs_shortest_paths("CDG");
+        assert_eq!(distances.len(), 1); // No outgoing edges from CDG
+
+        let bos = graph.node_id("BOS").unwrap();
+        let to_cdg = graph.bfs_distances_to(graph.node_id("CDG").unwrap());
+        assert_eq!(to_cdg[bos as usize], 1);
+        assert_eq!(to_cdg[graph.node_id("LHR").unwrap() as usize], 2);
+
+        assert_eq!(graph.connected_components().len(), 1); // Weakly connected
+    }
+
+    #[test]
+    fn test_build_directed_graph() {
+        let flights = sample_flights();
+        let graph = build_directed_graph(&flights, ServiceClass::Total);
+
+        assert!(graph.is_directed());
+        assert_eq!(graph.out_degree("GUM"), 1);
+        assert_eq!(graph.in_degree("GUM"), 0);
+        assert_eq!(graph.edge_weight("MAJ", "GUM"), None);
+        assert_eq!(
+            graph.busiest_routes()[0],
+            (("JFK".to_string(), "LHR".to_string()), 100)
+        );
+    }
+
+    #[test]
+    fn test_directed_graph_follows_flight_type() {
+        let departures = FlightData::for_test(2019, 1, "JFK", "LHR", "BA", 10, 0);
+        let arrivals = FlightData {
+            flight_type: FlightType::Arrivals,
+            ..FlightData::for_test(2019, 1, "JFK", "LHR", "BA", 7, 0)
+        };
+        let flights = [departures, arrivals];
+
+        let graph = build_directed_graph(&flights, ServiceClass::Total);
+        assert_eq!(graph.edge_weight("JFK", "LHR"), Some(10));
+        assert_eq!(graph.edge_weight("LHR", "JFK"), Some(7));
+
+        // Undirected graphs still merge both into one route
+        let graph = build_graph(&flights, ServiceClass::Total);
+        assert_eq!(graph.edge_weight("LHR", "JFK"), Some(17));
+    }
+}
@@ -0,0 +1,326 @@
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+use super::parallel::map_sources;
+use super::paths::{Candidate, EdgeCost};
+use super::{Graph, NodeId};
+
+/// Sources are processed in fixed-size blocks whose partial sums are added
+/// up in block order, so floating-point results do not depend on threading.
+const SOURCES_PER_BLOCK: usize = 32;
+
+/// Relative tolerance for treating two weighted path lengths as equal.
+const EPSILON: f64 = 1e-12;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BetweennessOptions {
+    /// Edge lengths; `EdgeCost::Hops` gives classic unweighted betweenness.
+    pub cost: EdgeCost,
+    /// Number of source airports to sample, or `None` for the exact result.
+    /// Sampled scores are scaled up to estimate the exact ones.
+    pub samples: Option<usize>,
+    /// Seed for choosing sample sources, so estimates are reproducible.
+    pub seed: u64,
+    /// Divide by the number of airport pairs not involving the node.
+    pub normalized: bool,
+}
+
+impl Default for BetweennessOptions {
+    fn default() -> Self {
+        Self {
+            cost: EdgeCost::Hops,
+            samples: None,
+            seed: 0,
+            normalized: false,
+        }
+    }
+}
+
+/// Per-thread buffers for one Brandes pass.
+struct Scratch {
+    order: Vec<NodeId>,
+    predecessors: Vec<Vec<NodeId>>,
+    path_counts: Vec<f64>,
+    distances: Vec<f64>,
+    dependencies: Vec<f64>,
+}
+
+impl Scratch {
+    fn new(node_count: usize) -> Self {
+        Self {
+            order: Vec::with_capacity(node_count),
+            predecessors: vec![Vec::new(); node_count],
+            path_counts: vec![0.0; node_count],
+            distances: vec![f64::INFINITY; node_count],
+            dependencies: vec![0.0; node_count],
+        }
+    }
+
+    fn reset(&mut self) {
+        self.order.clear();
+        self.predecessors.iter_mut().for_each(Vec::clear);
+        self.path_counts.fill(0.0);
+        self.distances.fill(f64::INFINITY);
+        self.dependencies.fill(0.0);
+    }
+}
+
+impl Graph {
+    /// Brandes betweenness centrality of every node, indexed by `NodeId`.
+    ///
+    /// Counts, for each node, the share of shortest paths between other
+    /// airport pairs that pass through it.
+    pub fn betweenness_scores(&self, options: &BetweennessOptions) -> Vec<f64> {
+        let node_count = self.node_count();
+        let strengths = self.strengths();
+        let sources = match options.samples {
+            Some(k) if k < node_count => sample_sources(node_count, k, options.seed),
+            _ => (0..node_count as NodeId).collect(),
+        };
+
+        let blocks: Vec<&[NodeId]> = sources.chunks(SOURCES_PER_BLOCK).collect();
+        let partials = map_sources(
+            blocks.len(),
+            || Scratch::new(node_count),
+            |scratch, block| {
+                let mut partial = vec![0.0; node_count];
+                for &source in blocks[block as usize] {
+                    self.accumulate_dependencies(source, options.cost, &strengths, scratch);
+                    for (total, &dependency) in partial.iter_mut().zip(&scratch.dependencies) {
+                        *total += dependency;
+                    }
+                }
+                partial
+            },
+            None,
+        );
+
+        let mut scores = vec![0.0; node_count];
+        for partial in partials {
+            for (score, value) in scores.iter_mut().zip(partial) {
+                *score += value;
+            }
+        }
+
+        // Every undirected path is found once from each end.
+        let mut scale = if self.is_directed() { 1.0 } else { 0.5 };
+        if !sources.is_empty() {
+            scale *= node_count as f64 / sources.len() as f64;
+        }
+        if options.normalized && node_count > 2 {
+            let pairs = ((node_count - 1) * (node_count - 2)) as f64;
+            scale *= if self.is_directed() {
+                1.0 / pairs
+            } else {
+                2.0 / pairs
+            };
+        }
+        scores.iter_mut().for_each(|score| *score *= scale);
+        scores
+    }
+
+    /// Betweenness centrality of every airport, keyed by code.
+    pub fn betweenness_centrality(&self, options: &BetweennessOptions) -> HashMap<String, f64> {
+        self.scores_by_code(self.betweenness_scores(options))
+    }
+
+    /// One Brandes pass: shortest-path DAG from `source`, then dependencies
+    /// accumulated back from the farthest nodes. Leaves them in `scratch`.
+    fn accumulate_dependencies(
+        &self,
+        source: NodeId,
+        cost: EdgeCost,
+        strengths: &[u64],
+        scratch: &mut Scratch,
+    ) {
+        scratch.reset();
+        scratch.path_counts[source as usize] = 1.0;
+        scratch.distances[source as usize] = 0.0;
+
+        if cost == EdgeCost::Hops {
+            self.count_paths_bfs(source, scratch);
+        } else {
+            self.count_paths_dijkstra(source, cost, strengths, scratch);
+        }
+
+        while let Some(node) = scratch.order.pop() {
+            let node = node as usize;
+            let coefficient = (1.0 + scratch.dependencies[node]) / scratch.path_counts[node];
+            for &previous in &scratch.predecessors[node] {
+                let previous = previous as usize;
+                scratch.dependencies[previous] += scratch.path_counts[previous] * coefficient;
+            }
+        }
+        scratch.dependencies[source as usize] = 0.0;
+    }
+
+    fn count_paths_bfs(&self, source: NodeId, scratch: &mut Scratch) {
+        let csr = self.adjacency();
+        let mut queue = VecDeque::from([source]);
+
+        while let Some(current) = queue.pop_front() {
+            scratch.order.push(current);
+            let next = scratch.distances[current as usize] + 1.0;
+            for &neighbor in csr.neighbors(current) {
+                let n = neighbor as usize;
+                if scratch.distances[n].is_infinite() {
+                    scratch.distances[n] = next;
+                    queue.push_back(neighbor);
+                }
+                if scratch.distances[n] == next {
+                    scratch.path_counts[n] += scratch.path_counts[current as usize];
+                    scratch.predecessors[n].push(current);
+                }
+            }
+        }
+    }
+
+    fn count_paths_dijkstra(
+        &self,
+        source: NodeId,
+        cost: EdgeCost,
+        strengths: &[u64],
+        scratch: &mut Scratch,
+    ) {
+        let csr = self.adjacency();
+        let mut settled = vec![false; self.node_count()];
+        let mut heap = BinaryHeap::from([Candidate {
+            distance: 0.0,
+            node: source,
+        }]);
+
+        while let Some(Candidate { distance, node }) = heap.pop() {
+            if settled[node as usize] || distance > scratch.distances[node as usize] {
+                continue;
+            }
+            settled[node as usize] = true;
+            scratch.order.push(node);
+
+            let strength = strengths[node as usize];
+            for (&neighbor, &weight) in csr.neighbors(node).iter().zip(csr.weights(node)) {
+                let n = neighbor as usize;
+                if settled[n] {
+                    continue;
+                }
+                let candidate = distance + cost.length(weight, strength);
+                let known = scratch.distances[n];
+                if candidate < known && !approx_eq(candidate, known) {
+                    scratch.distances[n] = candidate;
+                    scratch.path_counts[n] = scratch.path_counts[node as usize];
+                    scratch.predecessors[n].clear();
+                    scratch.predecessors[n].push(node);
+                    heap.push(Candidate {
+                        distance: candidate,
+                        node: neighbor,
+                    });
+                } else if approx_eq(candidate, known) {
+                    scratch.path_counts[n] += scratch.path_counts[node as usize];
+                    scratch.predecessors[n].push(node);
+                }
+            }
+        }
+    }
+}
+
+fn approx_eq(a: f64, b: f64) -> bool {
+    a == b || (b.is_finite() && (a - b).abs() <= EPSILON * a.abs().max(b.abs()))
+}
+
+/// `k` distinct node IDs chosen uniformly at random, in ascending order.
+fn sample_sources(node_count: usize, k: usize, seed: u64) -> Vec<NodeId> {
+    let mut ids: Vec<NodeId> = (0..node_count as NodeId).collect();
+    let mut state = seed;
+    for i in 0..k {
+        // Partial Fisher-Yates shuffle driven by SplitMix64
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        let j = i + (z % (node_count - i) as u64) as usize;
+        ids.swap(i, j);
+    }
+
+    let mut sample = ids[..k].to_vec();
+    sample.sort_unstable();
+    sample
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_betweenness_path_and_star() {
+        let mut graph = Graph::new();
+        graph.add_edge("A", "B", 1);
+        graph.add_edge("B", "C", 1);
+        graph.add_edge("C", "D", 1);
+        graph.add_edge("B", "E", 1);
+
+        let scores = graph.betweenness_centrality(&BetweennessOptions::default());
+        // B sits between A|E and C|D, and between A and E
+        assert_eq!(scores["B"], 5.0);
+        assert_eq!(scores["C"], 3.0);
+        assert_eq!(scores["A"], 0.0);
+
+        let normalized = graph.betweenness_centrality(&BetweennessOptions {
+            normalized: true,
+            ..BetweennessOptions::default()
+        });
+        assert!((normalized["B"] - 5.0 / 6.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_betweenness_splits_equal_paths() {
+        let mut graph = Graph::new();
+        graph.add_edge("A", "B", 1);
+        graph.add_edge("A", "C", 1);
+        graph.add_edge("B", "D", 1);
+        graph.add_edge("C", "D", 1);
+
+        let scores = graph.betweenness_centrality(&BetweennessOptions::default());
+        assert_eq!(scores["B"], 0.5); // Half of the A-D paths
+        assert_eq!(scores["C"], 0.5);
+    }
+
+    #[test]
+    fn test_weighted_betweenness_follows_busy_routes() {
+        let mut graph = Graph::new();
+        graph.add_edge("A", "B", 100);
+        graph.add_edge("B", "C", 100);
+        graph.add_edge("A", "C", 1);
+
+        let hops = graph.betweenness_centrality(&BetweennessOptions::default());
+        assert_eq!(hops["B"], 0.0);
+
+        let weighted = graph.betweenness_centrality(&BetweennessOptions {
+            cost: EdgeCost::InverseFlights,
+            ..BetweennessOptions::default()
+        });
+        assert_eq!(weighted["B"], 1.0);
+    }
+
+    #[test]
+    fn test_sampled_betweenness_is_reproducible() {
+        let mut graph = Graph::new();
+        for i in 0..20 {
+            graph.add_edge("HUB", &format!("S{}", i), 1);
+        }
+        let options = BetweennessOptions {
+            samples: Some(10),
+            seed: 7,
+            ..BetweennessOptions::default()
+        };
+
+        let first = graph.betweenness_scores(&options);
+        assert_eq!(first, graph.betweenness_scores(&options));
+
+        let sample = sample_sources(20, 10, 7);
+        assert_eq!(sample.len(), 10);
+        assert!(sample.windows(2).all(|w| w[0] < w[1]));
+
+        // Estimate is in the right range of the exact 190
+        let hub = first[graph.node_id("HUB").unwrap() as usize];
+        assert!(hub > 100.0 && hub < 300.0);
+    }
+}
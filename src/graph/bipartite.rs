@@ -0,0 +1,242 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use super::{Graph, NodeId};
+
+/// Which side of the US/foreign network an airport was seen on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeRole {
+    UsGateway,
+    Foreign,
+    /// Reported as a US gateway on some rows and a foreign airport on others.
+    Both,
+}
+
+impl NodeRole {
+    pub(super) fn merge(self, other: NodeRole) -> NodeRole {
+        if self == other {
+            self
+        } else {
+            NodeRole::Both
+        }
+    }
+
+    fn includes(self, side: NodeRole) -> bool {
+        self == side || self == NodeRole::Both
+    }
+}
+
+/// How edges of a one-mode projection are weighted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectionWeight {
+    /// Number of airports on the other side that both endpoints serve.
+    SharedNeighbors,
+    /// Sum over shared airports of the smaller of the two flight counts,
+    /// i.e. how much traffic the two endpoints overlap on.
+    MinFlights,
+}
+
+impl Graph {
+    /// Records that `code` appeared on the given side, adding it if needed.
+    pub fn set_role(&mut self, code: &str, role: NodeRole) {
+        let id = self.add_node(code) as usize;
+        if self.roles.len() <= id {
+            self.roles.resize(id + 1, None);
+        }
+        self.roles[id] = Some(match self.roles[id] {
+            Some(existing) => existing.merge(role),
+            None => role,
+        });
+    }
+
+    /// Role recorded for `code`, if any.
+    pub fn role(&self, code: &str) -> Option<NodeRole> {
+        let id = self.node_id(code)? as usize;
+        self.roles.get(id).copied().flatten()
+    }
+
+    /// Two-coloring of the graph (ignoring direction), or `None` if it has an
+    /// odd cycle. `true` marks the side of the lowest ID in each component.
+    pub fn bipartition(&self) -> Option<Vec<bool>> {
+        let mut colors: Vec<Option<bool>> = vec![None; self.node_count()];
+        let mut queue = VecDeque::new();
+
+        for start in 0..self.node_count() as NodeId {
+            if colors[start as usize].is_some() {
+                continue;
+            }
+            colors[start as usize] = Some(true);
+            queue.push_back(start);
+
+            while let Some(current) = queue.pop_front() {
+                let color = colors[current as usize].unwrap();
+                for (neighbor, _) in self.undirected_neighbors(current) {
+                    match colors[neighbor as usize] {
+                        None => {
+                            colors[neighbor as usize] = Some(!color);
+                            queue.push_back(neighbor);
+                        }
+                        Some(other) if other == color => return None,
+                        Some(_) => {}
+                    }
+                }
+            }
+        }
+
+        Some(colors.into_iter().map(|c| c.unwrap_or(true)).collect())
+    }
+
+    pub fn is_bipartite(&self) -> bool {
+        self.bipartition().is_some()
+    }
+
+    /// Whether every edge joins a US gateway to a foreign airport, with no
+    /// airport recorded on both sides or left without a role.
+    pub fn respects_roles(&self) -> bool {
+        let role = |id: NodeId| self.roles.get(id as usize).copied().flatten();
+        self.edges.keys().all(|&(a, b)| {
+            matches!(
+                (role(a), role(b)),
+                (Some(NodeRole::UsGateway), Some(NodeRole::Foreign))
+                    | (Some(NodeRole::Foreign), Some(NodeRole::UsGateway))
+            )
+        })
+    }
+
+    /// One-mode projection onto the airports with role `side`: two of them
+    /// are linked if they share neighbors on the other side.
+    ///
+    /// Projecting onto `UsGateway` shows which gateways serve the same
+    /// foreign markets. Airports recorded as `Both` appear in either
+    /// projection. All airports of the chosen side are kept, even if they
+    /// share nothing with any other.
+    pub fn project(&self, side: NodeRole, weight: ProjectionWeight) -> Graph {
+        let on_side = |id: NodeId| {
+            self.roles
+                .get(id as usize)
+                .copied()
+                .flatten()
+                .is_some_and(|role| role.includes(side))
+        };
+
+        let mut projection = Graph::new();
+        for id in 0..self.node_count() as NodeId {
+            if on_side(id) {
+                projection.add_node(self.node_code(id));
+                projection.set_role(self.node_code(id), side);
+            }
+        }
+
+        // Each shared neighbor contributes to every pair of its side-neighbors.
+        let mut overlaps: BTreeMap<(NodeId, NodeId), u64> = BTreeMap::new();
+        for shared in 0..self.node_count() as NodeId {
+            let members: Vec<(NodeId, u64)> = self
+                .undirected_neighbors(shared)
+                .into_iter()
+                .filter(|&(id, _)| id != shared && on_side(id))
+                .collect();
+            for (i, &(a, flights_a)) in members.iter().enumerate() {
+                for &(b, flights_b) in &members[i + 1..] {
+                    let amount = match weight {
+                        ProjectionWeight::SharedNeighbors => 1,
+                        ProjectionWeight::MinFlights => flights_a.min(flights_b),
+                    };
+                    *overlaps.entry((a.min(b), a.max(b))).or_insert(0) += amount;
+                }
+            }
+        }
+
+        for ((a, b), overlap) in overlaps {
+            projection.add_edge(self.node_code(a), self.node_code(b), overlap);
+        }
+        projection
+    }
+
+    /// Neighbors of `node` in either direction, with flights summed over
+    /// both directions, sorted by ID.
+    pub(super) fn undirected_neighbors(&self, node: NodeId) -> Vec<(NodeId, u64)> {
+        let out = self.adjacency();
+        let mut neighbors: Vec<(NodeId, u64)> = out
+            .neighbors(node)
+            .iter()
+            .copied()
+            .zip(out.weights(node).iter().copied())
+            .collect();
+
+        if self.is_directed() {
+            let incoming = self.in_adjacency();
+            let mut merged: HashMap<NodeId, u64> = neighbors.into_iter().collect();
+            for (&id, &w) in incoming.neighbors(node).iter().zip(incoming.weights(node)) {
+                *merged.entry(id).or_insert(0) += w;
+            }
+            neighbors = merged.into_iter().collect();
+            neighbors.sort_unstable();
+        }
+        neighbors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{FlightData, ServiceClass};
+    use crate::graph::{build_directed_graph, build_graph};
+
+    fn gateways() -> Graph {
+        build_graph(
+            &[
+                FlightData::for_test(2019, 1, "JFK", "LHR", "AA", 100, 0),
+                FlightData::for_test(2019, 1, "JFK", "CDG", "AF", 40, 0),
+                FlightData::for_test(2019, 1, "BOS", "LHR", "BA", 30, 0),
+                FlightData::for_test(2019, 1, "BOS", "CDG", "AF", 10, 0),
+                FlightData::for_test(2019, 1, "MIA", "LHR", "BA", 20, 0),
+                FlightData::for_test(2019, 1, "GUM", "MAJ", "PFQ", 5, 0),
+            ],
+            ServiceClass::Total,
+        )
+    }
+
+    #[test]
+    fn test_roles_and_bipartiteness() {
+        let mut graph = gateways();
+
+        assert_eq!(graph.role("JFK"), Some(NodeRole::UsGateway));
+        assert_eq!(graph.role("LHR"), Some(NodeRole::Foreign));
+        assert!(graph.is_bipartite());
+        assert!(graph.respects_roles());
+
+        graph.set_role("LHR", NodeRole::UsGateway);
+        assert_eq!(graph.role("LHR"), Some(NodeRole::Both));
+        assert!(!graph.respects_roles());
+
+        graph.add_edge("JFK", "BOS", 1); // Closes an odd cycle through LHR
+        assert!(!graph.is_bipartite());
+    }
+
+    #[test]
+    fn test_us_projection() {
+        let graph = gateways();
+        let shared = graph.project(NodeRole::UsGateway, ProjectionWeight::SharedNeighbors);
+
+        assert_eq!(shared.node_count(), 4); // GUM kept, though it shares nothing
+        assert_eq!(shared.edge_weight("JFK", "BOS"), Some(2)); // LHR and CDG
+        assert_eq!(shared.edge_weight("JFK", "MIA"), Some(1));
+        assert_eq!(shared.degree("GUM"), 0);
+
+        let flights = graph.project(NodeRole::UsGateway, ProjectionWeight::MinFlights);
+        assert_eq!(flights.edge_weight("JFK", "BOS"), Some(30 + 10));
+        assert_eq!(flights.edge_weight("BOS", "MIA"), Some(20));
+    }
+
+    #[test]
+    fn test_foreign_projection_of_directed_graph() {
+        let flights = vec![
+            FlightData::for_test(2019, 1, "JFK", "LHR", "AA", 100, 0),
+            FlightData::for_test(2019, 1, "JFK", "CDG", "AF", 40, 0),
+        ];
+        let graph = build_directed_graph(&flights, ServiceClass::Total);
+        let foreign = graph.project(NodeRole::Foreign, ProjectionWeight::MinFlights);
+
+        assert_eq!(foreign.node_count(), 2);
+        assert_eq!(foreign.edge_weight("LHR", "CDG"), Some(40));
+    }
+}
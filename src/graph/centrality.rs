@@ -0,0 +1,211 @@
+use std::collections::{HashMap, VecDeque};
+
+use super::parallel::{map_sources, Progress};
+use super::{Graph, NodeId, UNREACHABLE};
+
+impl Graph {
+    /// Harmonic centrality of every airport, keyed by code.
+    ///
+    /// Use `ranking::top_k` to pick out the leading airports.
+    pub fn harmonic_centrality(&self) -> HashMap<String, f64> {
+        self.scores_by_code(self.harmonic_scores())
+    }
+
+    /// Re-keys a per-`NodeId` score vector by airport code.
+    pub fn scores_by_code<T>(&self, scores: Vec<T>) -> HashMap<String, T> {
+        scores
+            .into_iter()
+            .enumerate()
+            .map(|(id, score)| (self.node_code(id as NodeId).to_string(), score))
+            .collect()
+    }
+
+    /// Harmonic centrality of every node, indexed by `NodeId`.
+    ///
+    /// One BFS is run per source, in parallel across CPU cores. Each score
+    /// only depends on its own BFS, so results are identical between runs.
+    pub fn harmonic_scores(&self) -> Vec<f64> {
+        self.centrality_scores(DistanceCentrality::Harmonic)
+    }
+
+    /// Like `harmonic_scores`, reporting progress after each source.
+    pub fn harmonic_scores_with_progress(&self, progress: Progress) -> Vec<f64> {
+        self.centrality_scores_with_progress(DistanceCentrality::Harmonic, progress)
+    }
+
+    /// Scores of every node for a distance-based measure, indexed by `NodeId`.
+    pub fn centrality_scores(&self, measure: DistanceCentrality) -> Vec<f64> {
+        self.scores_from_summaries(measure, self.distance_summaries_inner(None))
+    }
+
+    pub fn centrality_scores_with_progress(
+        &self,
+        measure: DistanceCentrality,
+        progress: Progress,
+    ) -> Vec<f64> {
+        self.scores_from_summaries(measure, self.distance_summaries_inner(Some(progress)))
+    }
+
+    /// Scores of every airport for a distance-based measure, keyed by code.
+    pub fn centrality(&self, measure: DistanceCentrality) -> HashMap<String, f64> {
+        self.scores_by_code(self.centrality_scores(measure))
+    }
+
+    /// Per-source BFS summaries that all distance-based measures derive from.
+    ///
+    /// Computing these once lets callers score several measures from a
+    /// single all-sources BFS.
+    pub fn distance_summaries(&self) -> Vec<DistanceSummary> {
+        self.distance_summaries_inner(None)
+    }
+
+    fn scores_from_summaries(
+        &self,
+        measure: DistanceCentrality,
+        summaries: Vec<DistanceSummary>,
+    ) -> Vec<f64> {
+        let node_count = self.node_count();
+        summaries
+            .iter()
+            .map(|summary| summary.score(measure, node_count))
+            .collect()
+    }
+
+    fn distance_summaries_inner(&self, progress: Option<Progress>) -> Vec<DistanceSummary> {
+        let node_count = self.node_count();
+        self.adjacency(); // Build the CSR once, before the workers share it
+
+        map_sources(
+            node_count,
+            || (vec![UNREACHABLE; node_count], VecDeque::new()),
+            |(distances, queue), source| {
+                distances.fill(UNREACHABLE);
+                self.bfs_into(self.adjacency(), source, distances, queue);
+
+                let mut summary = DistanceSummary::default();
+                for &d in distances.iter().filter(|&&d| d > 0 && d != UNREACHABLE) {
+                    summary.reached += 1;
+                    summary.distance_sum += d as u64;
+                    summary.harmonic_sum += 1.0 / d as f64;
+                }
+                summary
+            },
+            progress,
+        )
+    }
+}
+
+/// Centrality measures computed from hop distances.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistanceCentrality {
+    /// Sum of `1 / d` over all other nodes.
+    Harmonic,
+    /// Harmonic centrality divided by `n - 1`, comparable across graph sizes.
+    NormalizedHarmonic,
+    /// `r / sum(d)` over the `r` nodes the source can reach.
+    ///
+    /// Nothing penalizes reaching few nodes, so an airport one hop from its
+    /// only neighbour scores 1.0. On directed graphs, where most gateways
+    /// reach just the airports they fly to, the scores are not comparable;
+    /// use `WassermanFaust` there.
+    Closeness,
+    /// Closeness scaled by `r / (n - 1)`, so nodes in small components are
+    /// not scored as if they were central to the whole graph.
+    WassermanFaust,
+}
+
+/// What one BFS found: how many other nodes it reached, the sum of their
+/// distances and the sum of inverse distances.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DistanceSummary {
+    pub reached: usize,
+    pub distance_sum: u64,
+    pub harmonic_sum: f64,
+}
+
+impl DistanceSummary {
+    pub fn score(&self, measure: DistanceCentrality, node_count: usize) -> f64 {
+        let others = node_count.saturating_sub(1) as f64;
+        let closeness = if self.distance_sum > 0 {
+            self.reached as f64 / self.distance_sum as f64
+        } else {
+            0.0
+        };
+
+        match measure {
+            DistanceCentrality::Harmonic => self.harmonic_sum,
+            DistanceCentrality::NormalizedHarmonic if others > 0.0 => self.harmonic_sum / others,
+            DistanceCentrality::Closeness => closeness,
+            DistanceCentrality::WassermanFaust if others > 0.0 => {
+                closeness * self.reached as f64 / others
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_harmonic_scores_path_graph() {
+        let mut graph = Graph::new();
+        graph.add_edge("A", "B", 1);
+        graph.add_edge("B", "C", 1);
+        graph.add_edge("C", "D", 1);
+        graph.add_edge("X", "Y", 1); // Separate component
+
+        let calls = AtomicUsize::new(0);
+        let progress = |_: usize, _: usize| {
+            calls.fetch_add(1, Ordering::Relaxed);
+        };
+        let scores = graph.harmonic_scores_with_progress(&progress);
+        let score = |code: &str| scores[graph.node_id(code).unwrap() as usize];
+
+        assert_eq!(score("A"), 1.0 + 0.5 + 1.0 / 3.0);
+        assert_eq!(score("B"), 2.5);
+        assert_eq!(score("X"), 1.0);
+        assert_eq!(calls.load(Ordering::Relaxed), graph.node_count());
+        assert_eq!(scores, graph.harmonic_scores()); // Deterministic across runs
+    }
+
+    #[test]
+    fn test_closeness_variants() {
+        let mut graph = Graph::new();
+        graph.add_edge("A", "B", 1);
+        graph.add_edge("B", "C", 1);
+        graph.add_edge("X", "Y", 1); // Separate component
+
+        let normalized = graph.centrality(DistanceCentrality::NormalizedHarmonic);
+        assert_eq!(normalized["B"], 2.0 / 4.0);
+        assert_eq!(normalized["A"], 1.5 / 4.0);
+
+        let closeness = graph.centrality(DistanceCentrality::Closeness);
+        assert_eq!(closeness["B"], 1.0);
+        assert_eq!(closeness["A"], 2.0 / 3.0);
+        assert_eq!(closeness["X"], 1.0); // Fully central within its own pair
+
+        let wf = graph.centrality(DistanceCentrality::WassermanFaust);
+        assert_eq!(wf["B"], 1.0 * 2.0 / 4.0);
+        assert_eq!(wf["X"], 1.0 * 1.0 / 4.0); // Penalized for its small component
+    }
+
+    #[test]
+    fn test_isolated_and_single_node_scores() {
+        let mut graph = Graph::new();
+        graph.add_edge("A", "A", 1); // Self-loop only
+
+        let summary = graph.distance_summaries()[0];
+        assert_eq!(summary.reached, 0);
+        for measure in [
+            DistanceCentrality::Harmonic,
+            DistanceCentrality::NormalizedHarmonic,
+            DistanceCentrality::Closeness,
+            DistanceCentrality::WassermanFaust,
+        ] {
+            assert_eq!(summary.score(measure, graph.node_count()), 0.0);
+        }
+    }
+}
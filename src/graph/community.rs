@@ -0,0 +1,398 @@
+use std::collections::HashMap;
+
+use super::{Graph, NodeId};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LouvainOptions {
+    /// Modularity resolution: above 1 favours smaller communities, below 1
+    /// larger ones.
+    pub resolution: f64,
+    /// A pass over the nodes must raise modularity by more than this for
+    /// another pass to run.
+    pub tolerance: f64,
+    /// Upper bound on the number of aggregation levels.
+    pub max_levels: usize,
+}
+
+impl Default for LouvainOptions {
+    fn default() -> Self {
+        Self {
+            resolution: 1.0,
+            tolerance: 1e-7,
+            max_levels: 32,
+        }
+    }
+}
+
+/// Community of every node, indexed by `NodeId`.
+///
+/// Communities are numbered from 0 by decreasing size, so community 0 is
+/// the largest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Communities {
+    pub assignment: Vec<usize>,
+    pub count: usize,
+    pub modularity: f64,
+    /// Aggregation levels the Louvain method went through.
+    pub levels: usize,
+}
+
+/// Airports and traffic of one community.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommunitySummary {
+    pub community: usize,
+    pub airports: usize,
+    /// Flights on routes with both ends in the community.
+    pub internal_flights: u64,
+    /// Flights on routes with at least one end in the community.
+    pub total_flights: u64,
+    /// Busiest member airports by flights, busiest first.
+    pub top_airports: Vec<(String, u64)>,
+}
+
+/// Weighted undirected graph that the Louvain levels work on. Self-loops
+/// hold the weight inside an aggregated node, counted once.
+struct LevelGraph {
+    neighbors: Vec<Vec<(usize, f64)>>,
+    loops: Vec<f64>,
+}
+
+impl LevelGraph {
+    fn node_count(&self) -> usize {
+        self.loops.len()
+    }
+
+    /// Weighted degree, with a self-loop counting at both of its ends.
+    fn degree(&self, node: usize) -> f64 {
+        self.neighbors[node].iter().map(|&(_, w)| w).sum::<f64>() + 2.0 * self.loops[node]
+    }
+
+    /// Moves nodes between neighbouring communities until modularity stops
+    /// improving. Returns the community of every node and whether any node
+    /// moved.
+    fn local_moving(&self, options: &LouvainOptions, total: f64) -> (Vec<usize>, bool) {
+        let n = self.node_count();
+        let degrees: Vec<f64> = (0..n).map(|node| self.degree(node)).collect();
+        let mut community: Vec<usize> = (0..n).collect();
+        let mut community_degree = degrees.clone();
+        let mut weight_to = vec![0.0; n];
+        let mut touched: Vec<usize> = Vec::new();
+        let mut moved = false;
+
+        loop {
+            let mut gain_sum = 0.0;
+            for node in 0..n {
+                let current = community[node];
+                community_degree[current] -= degrees[node];
+
+                for &(neighbor, weight) in &self.neighbors[node] {
+                    let c = community[neighbor];
+                    if weight_to[c] == 0.0 {
+                        touched.push(c);
+                    }
+                    weight_to[c] += weight;
+                }
+
+                // Gain of joining `c`, up to a factor shared by all candidates
+                let gain = |c: usize, weight: f64| {
+                    weight
+                        - options.resolution * community_degree[c] * degrees[node] / (2.0 * total)
+                };
+                let stay = gain(current, weight_to[current]);
+                let (mut best, mut best_gain) = (current, stay);
+                touched.sort_unstable();
+                for &c in &touched {
+                    let candidate = gain(c, weight_to[c]);
+                    if candidate > best_gain {
+                        (best, best_gain) = (c, candidate);
+                    }
+                }
+                for c in touched.drain(..) {
+                    weight_to[c] = 0.0;
+                }
+
+                community_degree[best] += degrees[node];
+                if best != current {
+                    community[node] = best;
+                    gain_sum += (best_gain - stay) / total;
+                    moved = true;
+                }
+            }
+            if gain_sum <= options.tolerance {
+                break;
+            }
+        }
+
+        (community, moved)
+    }
+
+    /// Collapses each community into a single node, renumbering communities
+    /// densely in order of their lowest member. Returns the new graph and
+    /// each old node's new node.
+    fn aggregate(&self, community: &[usize]) -> (LevelGraph, Vec<usize>) {
+        let mut renumber = vec![usize::MAX; self.node_count()];
+        let mut next = 0;
+        let mapping: Vec<usize> = community
+            .iter()
+            .map(|&c| {
+                if renumber[c] == usize::MAX {
+                    renumber[c] = next;
+                    next += 1;
+                }
+                renumber[c]
+            })
+            .collect();
+
+        let mut loops = vec![0.0; next];
+        let mut weights: Vec<HashMap<usize, f64>> = vec![HashMap::new(); next];
+        for node in 0..self.node_count() {
+            let from = mapping[node];
+            loops[from] += self.loops[node];
+            for &(neighbor, weight) in &self.neighbors[node] {
+                let to = mapping[neighbor];
+                if from == to {
+                    loops[from] += weight / 2.0; // Seen from both ends
+                } else {
+                    *weights[from].entry(to).or_insert(0.0) += weight;
+                }
+            }
+        }
+
+        let neighbors = weights
+            .into_iter()
+            .map(|map| {
+                let mut list: Vec<(usize, f64)> = map.into_iter().collect();
+                list.sort_unstable_by_key(|&(to, _)| to);
+                list
+            })
+            .collect();
+        (LevelGraph { neighbors, loops }, mapping)
+    }
+}
+
+impl Graph {
+    /// Partitions the airports with the Louvain method, maximizing modularity
+    /// with flight counts as edge weights.
+    ///
+    /// Nodes are visited in ID order, so results are reproducible. Directed
+    /// graphs are treated as undirected.
+    pub fn louvain(&self, options: &LouvainOptions) -> Communities {
+        let n = self.node_count();
+        let mut neighbors: Vec<Vec<(usize, f64)>> = vec![Vec::new(); n];
+        let mut loops = vec![0.0; n];
+        let mut routes: Vec<(&(NodeId, NodeId), &u64)> = self.edges.iter().collect();
+        routes.sort_unstable();
+        for (&(a, b), &weight) in routes {
+            let (a, b) = (a as usize, b as usize);
+            if a == b {
+                loops[a] += weight as f64; // Once, as in `total` and `modularity`
+            } else {
+                neighbors[a].push((b, weight as f64));
+                neighbors[b].push((a, weight as f64));
+            }
+        }
+        let mut level = LevelGraph { neighbors, loops };
+        let total: f64 = self.edges.values().map(|&w| w as f64).sum();
+
+        let mut assignment: Vec<usize> = (0..n).collect();
+        let mut levels = 0;
+        if total > 0.0 {
+            while levels < options.max_levels {
+                let (community, moved) = level.local_moving(options, total);
+                if !moved {
+                    break;
+                }
+                let (next, mapping) = level.aggregate(&community);
+                for c in assignment.iter_mut() {
+                    *c = mapping[*c];
+                }
+                level = next;
+                levels += 1;
+            }
+        }
+
+        let (assignment, count) = relabel_by_size(&assignment);
+        Communities {
+            modularity: self.modularity(&assignment, options.resolution),
+            assignment,
+            count,
+            levels,
+        }
+    }
+
+    /// Louvain community of every airport, by code.
+    pub fn communities(&self, options: &LouvainOptions) -> HashMap<String, usize> {
+        self.scores_by_code(self.louvain(options).assignment)
+    }
+
+    /// Weighted modularity of a partition given as a community per `NodeId`.
+    pub fn modularity(&self, assignment: &[usize], resolution: f64) -> f64 {
+        let total: f64 = self.edges.values().map(|&w| w as f64).sum();
+        if total == 0.0 {
+            return 0.0;
+        }
+        let count = assignment.iter().max().map_or(0, |&c| c + 1);
+        let mut internal = vec![0.0; count];
+        let mut degree = vec![0.0; count];
+        for (&(a, b), &weight) in &self.edges {
+            let (ca, cb) = (assignment[a as usize], assignment[b as usize]);
+            if ca == cb {
+                internal[ca] += weight as f64;
+            }
+            degree[ca] += weight as f64;
+            degree[cb] += weight as f64;
+        }
+
+        internal
+            .iter()
+            .zip(&degree)
+            .map(|(&inside, &deg)| inside / total - resolution * (deg / (2.0 * total)).powi(2))
+            .sum()
+    }
+
+    /// Size and traffic of every community, in community order.
+    pub fn community_summaries(
+        &self,
+        communities: &Communities,
+        top: usize,
+    ) -> Vec<CommunitySummary> {
+        let strengths = self.strengths();
+        let mut summaries: Vec<CommunitySummary> = (0..communities.count)
+            .map(|community| CommunitySummary {
+                community,
+                airports: 0,
+                internal_flights: 0,
+                total_flights: 0,
+                top_airports: Vec::new(),
+            })
+            .collect();
+
+        let mut members: Vec<Vec<NodeId>> = vec![Vec::new(); communities.count];
+        for (node, &c) in communities.assignment.iter().enumerate() {
+            members[c].push(node as NodeId);
+            summaries[c].airports += 1;
+        }
+        for (&(a, b), &weight) in &self.edges {
+            let (ca, cb) = (
+                communities.assignment[a as usize],
+                communities.assignment[b as usize],
+            );
+            summaries[ca].total_flights += weight;
+            if ca == cb {
+                summaries[ca].internal_flights += weight;
+            } else {
+                summaries[cb].total_flights += weight;
+            }
+        }
+
+        for (summary, mut nodes) in summaries.iter_mut().zip(members) {
+            nodes.sort_by(|&a, &b| {
+                strengths[b as usize]
+                    .cmp(&strengths[a as usize])
+                    .then_with(|| self.node_code(a).cmp(self.node_code(b)))
+            });
+            summary.top_airports = nodes
+                .into_iter()
+                .take(top)
+                .map(|node| (self.node_code(node).to_string(), strengths[node as usize]))
+                .collect();
+        }
+        summaries
+    }
+}
+
+/// Renumbers communities by decreasing size, ties by lowest member, and
+/// returns the new assignment with the number of communities.
+fn relabel_by_size(assignment: &[usize]) -> (Vec<usize>, usize) {
+    let mut sizes: HashMap<usize, (usize, usize)> = HashMap::new(); // Label -> (size, lowest member)
+    for (node, &c) in assignment.iter().enumerate() {
+        sizes.entry(c).or_insert((0, node)).0 += 1;
+    }
+    let mut order: Vec<(usize, (usize, usize))> = sizes.into_iter().collect();
+    order.sort_by(|a, b| b.1 .0.cmp(&a.1 .0).then_with(|| a.1 .1.cmp(&b.1 .1)));
+
+    let label: HashMap<usize, usize> = order
+        .iter()
+        .enumerate()
+        .map(|(new, &(old, _))| (old, new))
+        .collect();
+    (assignment.iter().map(|c| label[c]).collect(), order.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_triangles() -> Graph {
+        let mut graph = Graph::new();
+        for (a, b, w) in [
+            ("A", "B", 1),
+            ("B", "C", 1),
+            ("A", "C", 1),
+            ("C", "D", 1),
+            ("D", "E", 1),
+            ("E", "F", 1),
+            ("D", "F", 1),
+        ] {
+            graph.add_edge(a, b, w);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_louvain_two_triangles() {
+        let graph = two_triangles();
+        let communities = graph.louvain(&LouvainOptions::default());
+
+        assert_eq!(communities.count, 2);
+        assert_eq!(communities.assignment, [0, 0, 0, 1, 1, 1]);
+        assert!((communities.modularity - (6.0 / 7.0 - 0.5)).abs() < 1e-12);
+
+        let by_code = graph.communities(&LouvainOptions::default());
+        assert_eq!(by_code["A"], by_code["C"]);
+        assert_ne!(by_code["C"], by_code["D"]);
+
+        let summaries = graph.community_summaries(&communities, 1);
+        assert_eq!(summaries[0].airports, 3);
+        assert_eq!(
+            (summaries[0].internal_flights, summaries[0].total_flights),
+            (3, 4)
+        );
+        assert_eq!(summaries[1].top_airports, [("D".to_string(), 3)]);
+    }
+
+    #[test]
+    fn test_weights_shape_communities() {
+        // A heavy A-B pair and a heavy C-D pair joined by light edges
+        let mut graph = Graph::new();
+        graph.add_edge("A", "B", 100);
+        graph.add_edge("C", "D", 100);
+        graph.add_edge("B", "C", 1);
+        graph.add_edge("A", "D", 1);
+        let communities = graph.louvain(&LouvainOptions::default());
+        assert_eq!(communities.count, 2);
+        assert_eq!(communities.assignment[0], communities.assignment[1]);
+        assert_ne!(communities.assignment[1], communities.assignment[2]);
+
+        // Everything in one community has zero modularity
+        assert_eq!(graph.modularity(&[0, 0, 0, 0], 1.0), 0.0);
+        assert_eq!(Graph::new().louvain(&LouvainOptions::default()).count, 0);
+    }
+
+    #[test]
+    fn test_self_loops_count_once() {
+        // B and D belong together; their self-loops must not hold them apart
+        let mut graph = Graph::new();
+        graph.add_edge("B", "D", 9);
+        graph.add_edge("B", "B", 7);
+        graph.add_edge("D", "D", 5);
+        graph.add_edge("E", "E", 4);
+        let communities = graph.communities(&LouvainOptions::default());
+        assert_eq!(communities["B"], communities["D"]);
+        assert_ne!(communities["B"], communities["E"]);
+
+        // m = 25: 21/25 - (42/50)^2 for B and D, 4/25 - (8/50)^2 for E
+        let modularity = graph.louvain(&LouvainOptions::default()).modularity;
+        assert!((modularity - 0.2688).abs() < 1e-12);
+    }
+}
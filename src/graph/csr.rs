@@ -0,0 +1,69 @@
+use super::interner::NodeId;
+
+/// Compressed sparse row adjacency: the neighbors of node `i` are
+/// `targets[offsets[i]..offsets[i + 1]]`, sorted by ID, with the matching
+/// edge weights in `weights`.
+#[derive(Debug, Clone, Default)]
+pub struct Csr {
+    offsets: Vec<usize>,
+    targets: Vec<NodeId>,
+    weights: Vec<u64>,
+}
+
+impl Csr {
+    /// Builds the adjacency for `node_count` nodes from directed arcs
+    /// `(from, to, weight)`. Undirected edges must be passed in both directions.
+    pub fn from_arcs(node_count: usize, arcs: &[(NodeId, NodeId, u64)]) -> Self {
+        let mut offsets = vec![0; node_count + 1];
+        for &(from, _, _) in arcs {
+            offsets[from as usize + 1] += 1;
+        }
+        for i in 0..node_count {
+            offsets[i + 1] += offsets[i];
+        }
+
+        let mut sorted = arcs.to_vec();
+        sorted.sort_unstable_by_key(|&(from, to, _)| (from, to));
+
+        Self {
+            offsets,
+            targets: sorted.iter().map(|&(_, to, _)| to).collect(),
+            weights: sorted.iter().map(|&(_, _, weight)| weight).collect(),
+        }
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.offsets.len().saturating_sub(1)
+    }
+
+    pub fn neighbors(&self, node: NodeId) -> &[NodeId] {
+        let node = node as usize;
+        &self.targets[self.offsets[node]..self.offsets[node + 1]]
+    }
+
+    pub fn weights(&self, node: NodeId) -> &[u64] {
+        let node = node as usize;
+        &self.weights[self.offsets[node]..self.offsets[node + 1]]
+    }
+
+    pub fn degree(&self, node: NodeId) -> usize {
+        let node = node as usize;
+        self.offsets[node + 1] - self.offsets[node]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csr_from_arcs() {
+        let csr = Csr::from_arcs(3, &[(2, 0, 7), (0, 2, 7), (0, 1, 3), (1, 0, 3)]);
+
+        assert_eq!(csr.node_count(), 3);
+        assert_eq!(csr.neighbors(0), &[1, 2]); // Sorted by target ID
+        assert_eq!(csr.weights(0), &[3, 7]);
+        assert_eq!(csr.neighbors(1), &[0]);
+        assert_eq!(csr.degree(2), 1);
+    }
+}
@@ -0,0 +1,213 @@
+use super::{Graph, NodeId};
+
+/// An airport whose removal splits its component.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArticulationPoint {
+    pub airport: String,
+    /// Sizes of the pieces its component falls into without it, largest
+    /// first.
+    pub pieces: Vec<usize>,
+    /// Airports cut off from the largest piece.
+    pub cut_off: usize,
+}
+
+/// A route whose removal splits its component in two.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bridge {
+    /// Endpoint on the side that stays with the larger part.
+    pub from: String,
+    /// Endpoint on the side that gets cut off.
+    pub to: String,
+    pub flights: u64,
+    /// Airports on the cut-off side, `to` included.
+    pub cut_off: usize,
+}
+
+const UNVISITED: usize = usize::MAX;
+
+impl Graph {
+    /// Airports whose removal disconnects part of the network, the most
+    /// damaging first. Directed graphs are treated as undirected.
+    pub fn articulation_points(&self) -> Vec<ArticulationPoint> {
+        self.cut_structure().0
+    }
+
+    /// Routes whose removal disconnects part of the network, the most
+    /// damaging first. Directed graphs are treated as undirected.
+    pub fn bridges(&self) -> Vec<Bridge> {
+        self.cut_structure().1
+    }
+
+    /// Articulation points and bridges from one iterative Tarjan DFS per
+    /// component, tracking subtree sizes to measure what each cut removes.
+    fn cut_structure(&self) -> (Vec<ArticulationPoint>, Vec<Bridge>) {
+        let n = self.node_count();
+        let neighbors: Vec<Vec<(NodeId, u64)>> = (0..n as NodeId)
+            .map(|node| self.undirected_neighbors(node))
+            .collect();
+        let mut discovered = vec![UNVISITED; n];
+        let mut low = vec![0; n];
+        let mut size = vec![1; n];
+        let mut separated: Vec<Vec<usize>> = vec![Vec::new(); n]; // Child subtrees cut off by removing the node
+        let mut tree_bridges: Vec<(NodeId, NodeId, u64)> = Vec::new(); // (parent, child, flights)
+        let mut points = Vec::new();
+        let mut bridges = Vec::new();
+        let mut time = 0;
+
+        for root in 0..n as NodeId {
+            if discovered[root as usize] != UNVISITED {
+                continue;
+            }
+            let start = time;
+            let mut order = Vec::new();
+            let mut stack: Vec<(NodeId, Option<NodeId>, usize)> = vec![(root, None, 0)];
+            discovered[root as usize] = time;
+            low[root as usize] = time;
+            time += 1;
+
+            while let Some((node, parent, next)) = stack.last_mut() {
+                let (node, parent) = (*node, *parent);
+                if let Some(&(neighbor, _)) = neighbors[node as usize].get(*next) {
+                    *next += 1;
+                    if Some(neighbor) == parent {
+                        continue; // Routes are aggregated, so there is one edge back
+                    }
+                    let n = neighbor as usize;
+                    if discovered[n] == UNVISITED {
+                        discovered[n] = time;
+                        low[n] = time;
+                        time += 1;
+                        stack.push((neighbor, Some(node), 0));
+                    } else {
+                        low[node as usize] = low[node as usize].min(discovered[n]);
+                    }
+                    continue;
+                }
+
+                stack.pop();
+                order.push(node);
+                if let Some(parent) = parent {
+                    let (child, p) = (node as usize, parent as usize);
+                    size[p] += size[child];
+                    low[p] = low[p].min(low[child]);
+                    if low[child] >= discovered[p] {
+                        separated[p].push(size[child]);
+                    }
+                    if low[child] > discovered[p] {
+                        let flights = neighbors[p]
+                            .iter()
+                            .find(|&&(id, _)| id == node)
+                            .map_or(0, |&(_, w)| w);
+                        tree_bridges.push((parent, node, flights));
+                    }
+                }
+            }
+
+            let component = time - start;
+            for &node in &order {
+                let mut pieces = separated[node as usize].clone();
+                let rest = component - 1 - pieces.iter().sum::<usize>();
+                if rest > 0 {
+                    pieces.push(rest);
+                }
+                if pieces.len() > 1 {
+                    pieces.sort_unstable_by(|a, b| b.cmp(a));
+                    points.push(ArticulationPoint {
+                        airport: self.node_code(node).to_string(),
+                        cut_off: pieces[1..].iter().sum(),
+                        pieces,
+                    });
+                }
+            }
+            for (parent, child, flights) in tree_bridges.drain(..) {
+                let below = size[child as usize];
+                let (from, to, cut_off) = if below <= component - below {
+                    (parent, child, below)
+                } else {
+                    (child, parent, component - below)
+                };
+                bridges.push(Bridge {
+                    from: self.node_code(from).to_string(),
+                    to: self.node_code(to).to_string(),
+                    flights,
+                    cut_off,
+                });
+            }
+        }
+
+        points.sort_by(|a, b| {
+            b.cut_off
+                .cmp(&a.cut_off)
+                .then_with(|| a.airport.cmp(&b.airport))
+        });
+        bridges.sort_by(|a, b| {
+            b.cut_off
+                .cmp(&a.cut_off)
+                .then_with(|| (&a.from, &a.to).cmp(&(&b.from, &b.to)))
+        });
+        (points, bridges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pacific() -> Graph {
+        let mut graph = Graph::new();
+        for (a, b, w) in [
+            ("JFK", "LHR", 50),
+            ("LHR", "CDG", 40),
+            ("CDG", "JFK", 30),
+            ("JFK", "HNL", 20),
+            ("HNL", "MAJ", 8),
+            ("MAJ", "KWA", 2),
+            ("HNL", "GUM", 6),
+        ] {
+            graph.add_edge(a, b, w);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_articulation_points() {
+        let points = pacific().articulation_points();
+        let summary: Vec<(&str, usize, &[usize])> = points
+            .iter()
+            .map(|p| (p.airport.as_str(), p.cut_off, p.pieces.as_slice()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("HNL", 3, &[3, 2, 1][..]),
+                ("JFK", 2, &[4, 2][..]),
+                ("MAJ", 1, &[5, 1][..]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bridges() {
+        let bridges = pacific().bridges();
+        let summary: Vec<(&str, &str, u64, usize)> = bridges
+            .iter()
+            .map(|b| (b.from.as_str(), b.to.as_str(), b.flights, b.cut_off))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("HNL", "JFK", 20, 3),
+                ("HNL", "MAJ", 8, 2),
+                ("HNL", "GUM", 6, 1),
+                ("MAJ", "KWA", 2, 1),
+            ]
+        );
+
+        let mut cycle = Graph::new();
+        cycle.add_edge("A", "B", 1);
+        cycle.add_edge("B", "C", 1);
+        cycle.add_edge("C", "A", 1);
+        assert!(cycle.bridges().is_empty());
+        assert!(cycle.articulation_points().is_empty());
+    }
+}
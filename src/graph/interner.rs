@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+/// Dense integer identifier for an airport within one `Graph`.
+pub type NodeId = u32;
+
+/// Two-way mapping between airport codes and `NodeId`s.
+///
+/// IDs are handed out in first-seen order, starting at zero, so they can be
+/// used directly as indices into per-node vectors.
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    ids: HashMap<String, NodeId>,
+    codes: Vec<String>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the ID for `code`, assigning the next free one if it is new.
+    pub fn intern(&mut self, code: &str) -> NodeId {
+        if let Some(&id) = self.ids.get(code) {
+            return id;
+        }
+        let id = self.codes.len() as NodeId;
+        self.ids.insert(code.to_string(), id);
+        self.codes.push(code.to_string());
+        id
+    }
+
+    pub fn get(&self, code: &str) -> Option<NodeId> {
+        self.ids.get(code).copied()
+    }
+
+    /// Airport code for an ID handed out by this interner.
+    pub fn code(&self, id: NodeId) -> &str {
+        &self.codes[id as usize]
+    }
+
+    pub fn codes(&self) -> &[String] {
+        &self.codes
+    }
+
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern_round_trip() {
+        let mut interner = Interner::new();
+        let jfk = interner.intern("JFK");
+        let lhr = interner.intern("LHR");
+
+        assert_eq!(interner.intern("JFK"), jfk); // Re-interning returns the same ID
+        assert_eq!((jfk, lhr), (0, 1));
+        assert_eq!(interner.code(lhr), "LHR");
+        assert_eq!(interner.get("LHR"), Some(lhr));
+        assert_eq!(interner.get("CDG"), None);
+        assert_eq!(interner.len(), 2);
+    }
+}
@@ -0,0 +1,335 @@
+use std::collections::{BTreeMap, HashMap};
+
+use super::{Graph, Interner, NodeId};
+use crate::data::{CarrierGroup, FlightData, ServiceClass};
+use crate::ranking::top_k;
+
+/// What the layers of a multiplex network are split by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LayerBy {
+    /// One layer per reporting carrier code.
+    #[default]
+    Carrier,
+    /// One layer for US carriers and one for foreign carriers.
+    CarrierGroup,
+}
+
+impl LayerBy {
+    fn layer_of(self, flight: &FlightData) -> &str {
+        match self {
+            LayerBy::Carrier => &flight.carrier,
+            LayerBy::CarrierGroup => match flight.carrier_group {
+                CarrierGroup::Us => "US",
+                CarrierGroup::Foreign => "Foreign",
+            },
+        }
+    }
+}
+
+/// Airport network split into layers, e.g. one per carrier, over a shared
+/// set of airports.
+///
+/// Every layer uses the same `NodeId`s, so per-airport values can be compared
+/// across layers directly. Layers only store their own routes; `layer`
+/// builds a standalone `Graph` of one of them when needed.
+#[derive(Debug, Default)]
+pub struct Multiplex {
+    nodes: Interner,
+    layers: BTreeMap<String, HashMap<(NodeId, NodeId), u64>>, // Layer -> Route (lower ID first) -> Weight
+}
+
+/// Size, connectivity and busiest airports of one layer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerSummary {
+    pub layer: String,
+    /// Airports with at least one route in this layer.
+    pub nodes: usize,
+    pub edges: usize,
+    pub flights: u64,
+    pub components: usize,
+    pub largest_component: usize,
+    /// Airports with the most flights in this layer, busiest first.
+    pub hubs: Vec<(String, u64)>,
+}
+
+/// How many routes two layers have in common.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayerOverlap {
+    pub first: String,
+    pub second: String,
+    pub shared: usize,
+    /// Shared routes over routes flown by either layer.
+    pub jaccard: f64,
+}
+
+/// Builds a multiplex network with one layer per `by` value, using the
+/// flights of one class of service.
+pub fn build_multiplex(flights: &[FlightData], class: ServiceClass, by: LayerBy) -> Multiplex {
+    let mut multiplex = Multiplex::default();
+
+    for flight in flights {
+        let count = flight.flights(class);
+        if count == 0 {
+            continue;
+        }
+        let a = multiplex.nodes.intern(&flight.us_airport);
+        let b = multiplex.nodes.intern(&flight.foreign_airport);
+        let layer = by.layer_of(flight);
+        if !multiplex.layers.contains_key(layer) {
+            multiplex.layers.insert(layer.to_string(), HashMap::new());
+        }
+        let edges = multiplex.layers.get_mut(layer).unwrap();
+        *edges.entry((a.min(b), a.max(b))).or_insert(0) += count as u64;
+    }
+
+    multiplex
+}
+
+impl Multiplex {
+    /// Airports across all layers.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn node_code(&self, id: NodeId) -> &str {
+        self.nodes.code(id)
+    }
+
+    pub fn layer_count(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Layer names in sorted order.
+    pub fn layer_names(&self) -> impl Iterator<Item = &str> {
+        self.layers.keys().map(String::as_str)
+    }
+
+    /// One layer as a standalone graph containing only the airports it serves.
+    pub fn layer(&self, name: &str) -> Option<Graph> {
+        let edges = self.layers.get(name)?;
+        let mut routes: Vec<(&(NodeId, NodeId), &u64)> = edges.iter().collect();
+        routes.sort_unstable(); // Keep IDs in the layer graph stable between runs
+
+        let mut graph = Graph::new();
+        for (&(a, b), &weight) in routes {
+            graph.add_edge(self.nodes.code(a), self.nodes.code(b), weight);
+        }
+        Some(graph)
+    }
+
+    /// All layers merged back into one graph, as `build_graph` would give.
+    pub fn aggregate(&self) -> Graph {
+        let mut graph = Graph::new();
+        for layer in self.layers.keys() {
+            let mut routes: Vec<_> = self.layers[layer].iter().collect();
+            routes.sort_unstable();
+            for (&(a, b), &weight) in routes {
+                graph.add_edge(self.nodes.code(a), self.nodes.code(b), weight);
+            }
+        }
+        graph
+    }
+
+    /// Metrics of every layer, busiest layer first.
+    pub fn layer_summaries(&self, hubs: usize) -> Vec<LayerSummary> {
+        let mut summaries: Vec<LayerSummary> = self
+            .layers
+            .keys()
+            .map(|name| {
+                let graph = self.layer(name).unwrap();
+                let components = graph.component_ids();
+                let strengths = graph.scores_by_code(graph.strengths());
+                LayerSummary {
+                    layer: name.clone(),
+                    nodes: graph.node_count(),
+                    edges: graph.edge_count(),
+                    flights: self.layers[name].values().copied().sum(),
+                    components: components.len(),
+                    largest_component: components.iter().map(Vec::len).max().unwrap_or(0),
+                    hubs: top_k(&strengths, hubs, |_| true),
+                }
+            })
+            .collect();
+
+        summaries.sort_by(|a, b| {
+            b.flights
+                .cmp(&a.flights)
+                .then_with(|| a.layer.cmp(&b.layer))
+        });
+        summaries
+    }
+
+    /// Jaccard overlap of the route sets of two layers, `None` if either
+    /// layer does not exist.
+    pub fn edge_overlap(&self, first: &str, second: &str) -> Option<f64> {
+        let (a, b) = (self.layers.get(first)?, self.layers.get(second)?);
+        let shared = a.keys().filter(|route| b.contains_key(route)).count();
+        let union = a.len() + b.len() - shared;
+        Some(if union == 0 {
+            0.0
+        } else {
+            shared as f64 / union as f64
+        })
+    }
+
+    /// Every pair of layers sharing at least one route, most shared routes
+    /// first.
+    pub fn overlaps(&self) -> Vec<LayerOverlap> {
+        let names: Vec<&String> = self.layers.keys().collect();
+        let mut by_route: HashMap<(NodeId, NodeId), Vec<usize>> = HashMap::new();
+        for (index, name) in names.iter().enumerate() {
+            for route in self.layers[*name].keys() {
+                by_route.entry(*route).or_default().push(index); // Indices ascending
+            }
+        }
+
+        let mut shared: HashMap<(usize, usize), usize> = HashMap::new();
+        for layers in by_route.values() {
+            for (i, &first) in layers.iter().enumerate() {
+                for &second in &layers[i + 1..] {
+                    *shared.entry((first, second)).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let mut overlaps: Vec<LayerOverlap> = shared
+            .into_iter()
+            .map(|((first, second), shared)| {
+                let union =
+                    self.layers[names[first]].len() + self.layers[names[second]].len() - shared;
+                LayerOverlap {
+                    first: names[first].clone(),
+                    second: names[second].clone(),
+                    shared,
+                    jaccard: shared as f64 / union as f64,
+                }
+            })
+            .collect();
+
+        overlaps.sort_by(|a, b| {
+            b.shared
+                .cmp(&a.shared)
+                .then_with(|| (&a.first, &a.second).cmp(&(&b.first, &b.second)))
+        });
+        overlaps
+    }
+
+    /// Multiplex participation coefficient of every airport, indexed by
+    /// `NodeId`.
+    ///
+    /// `P = M / (M - 1) * (1 - sum over layers of (k / o)^2)`, with `k` the
+    /// airport's degree in a layer, `o` its degree summed over all `M`
+    /// layers. 1 means its routes are spread evenly over every layer, 0 that
+    /// they all belong to one.
+    pub fn participation_scores(&self) -> Vec<f64> {
+        let layer_count = self.layers.len();
+        let mut total = vec![0u32; self.node_count()];
+        let mut squares = vec![0f64; self.node_count()];
+
+        for edges in self.layers.values() {
+            let mut degree = vec![0u32; self.node_count()];
+            for &(a, b) in edges.keys() {
+                degree[a as usize] += 1;
+                degree[b as usize] += 1;
+            }
+            for (node, &k) in degree.iter().enumerate() {
+                total[node] += k;
+                squares[node] += (k as f64).powi(2);
+            }
+        }
+
+        if layer_count < 2 {
+            return vec![0.0; self.node_count()];
+        }
+        let scale = layer_count as f64 / (layer_count - 1) as f64;
+        total
+            .iter()
+            .zip(&squares)
+            .map(|(&o, &sum)| {
+                if o == 0 {
+                    0.0
+                } else {
+                    scale * (1.0 - sum / (o as f64).powi(2))
+                }
+            })
+            .collect()
+    }
+
+    /// Multiplex participation coefficient of every airport, by code.
+    pub fn participation(&self) -> HashMap<String, f64> {
+        self.participation_scores()
+            .into_iter()
+            .enumerate()
+            .map(|(id, score)| (self.node_code(id as NodeId).to_string(), score))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::build_graph;
+
+    fn sample() -> Vec<FlightData> {
+        vec![
+            FlightData::for_test(2019, 1, "JFK", "LHR", "AA", 10, 0),
+            FlightData::for_test(2019, 1, "JFK", "LHR", "BA", 20, 0),
+            FlightData::for_test(2019, 1, "JFK", "MAD", "AA", 5, 0),
+            FlightData::for_test(2019, 1, "MIA", "MAD", "AA", 5, 0),
+            FlightData::for_test(2019, 1, "BOS", "LHR", "BA", 7, 0),
+            FlightData::for_test(2019, 1, "ORD", "FRA", "LH", 0, 4),
+        ]
+    }
+
+    #[test]
+    fn test_multiplex_layers() {
+        let flights = sample();
+        let multiplex = build_multiplex(&flights, ServiceClass::Total, LayerBy::Carrier);
+        assert_eq!(multiplex.layer_count(), 3);
+        assert_eq!(
+            multiplex.layer_names().collect::<Vec<_>>(),
+            ["AA", "BA", "LH"]
+        );
+
+        let aa = multiplex.layer("AA").unwrap();
+        assert_eq!((aa.node_count(), aa.edge_count()), (4, 3));
+        assert!(multiplex.layer("UA").is_none());
+
+        let aggregate = multiplex.aggregate();
+        let merged = build_graph(&flights, ServiceClass::Total);
+        assert_eq!(aggregate.edge_count(), merged.edge_count());
+        assert_eq!(aggregate.edge_weight("JFK", "LHR"), Some(30));
+
+        let summaries = multiplex.layer_summaries(1);
+        assert_eq!(summaries[0].layer, "BA");
+        assert_eq!(summaries[0].flights, 27);
+        assert_eq!(summaries[0].hubs, [("LHR".to_string(), 27)]);
+        assert_eq!(
+            (summaries[1].components, summaries[1].largest_component),
+            (1, 4)
+        );
+
+        let groups = build_multiplex(&flights, ServiceClass::Total, LayerBy::CarrierGroup);
+        assert_eq!(groups.layer_names().collect::<Vec<_>>(), ["US"]);
+    }
+
+    #[test]
+    fn test_cross_layer_metrics() {
+        let multiplex = build_multiplex(&sample(), ServiceClass::Total, LayerBy::Carrier);
+
+        // AA and BA share JFK-LHR out of JFK-LHR, JFK-MAD, MIA-MAD, BOS-LHR
+        assert_eq!(multiplex.edge_overlap("AA", "BA"), Some(0.25));
+        assert_eq!(multiplex.edge_overlap("AA", "LH"), Some(0.0));
+        assert_eq!(multiplex.edge_overlap("AA", "UA"), None);
+
+        let overlaps = multiplex.overlaps();
+        assert_eq!(overlaps.len(), 1);
+        assert_eq!((overlaps[0].first.as_str(), overlaps[0].shared), ("AA", 1));
+
+        let participation = multiplex.participation();
+        // JFK: degree 2 in AA, 1 in BA, 0 in LH
+        let expected = 1.5 * (1.0 - (4.0 + 1.0) / 9.0);
+        assert!((participation["JFK"] - expected).abs() < 1e-12);
+        assert_eq!(participation["FRA"], 0.0);
+    }
+}
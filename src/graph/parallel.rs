@@ -0,0 +1,78 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use super::interner::NodeId;
+
+/// Callback reporting `(sources_done, sources_total)` during all-sources runs.
+///
+/// It is called from worker threads, once per finished source.
+pub type Progress<'a> = &'a (dyn Fn(usize, usize) + Sync);
+
+/// Runs `f` once for every node ID in `0..node_count`, spreading the sources
+/// over the available CPU cores.
+///
+/// Each worker thread gets its own scratch state from `scratch`, so per-source
+/// buffers are allocated once per thread. Results come back in ID order no
+/// matter how the work was scheduled, which keeps callers deterministic.
+pub(crate) fn map_sources<S, T, I, F>(
+    node_count: usize,
+    scratch: I,
+    f: F,
+    progress: Option<Progress>,
+) -> Vec<T>
+where
+    T: Send,
+    I: Fn() -> S + Sync,
+    F: Fn(&mut S, NodeId) -> T + Sync,
+{
+    let threads = thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(node_count.max(1));
+    let next = AtomicUsize::new(0);
+    let done = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(node_count));
+
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                let mut state = scratch();
+                let mut local = Vec::new();
+                loop {
+                    let source = next.fetch_add(1, Ordering::Relaxed);
+                    if source >= node_count {
+                        break;
+                    }
+                    local.push((source, f(&mut state, source as NodeId)));
+                    let finished = done.fetch_add(1, Ordering::Relaxed) + 1;
+                    if let Some(progress) = progress {
+                        progress(finished, node_count);
+                    }
+                }
+                results.lock().unwrap().extend(local);
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_unstable_by_key(|&(source, _)| source);
+    results.into_iter().map(|(_, value)| value).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_sources_in_id_order() {
+        let calls = AtomicUsize::new(0);
+        let progress = |_done: usize, total: usize| {
+            assert_eq!(total, 100);
+            calls.fetch_add(1, Ordering::Relaxed);
+        };
+        let squares = map_sources(100, || (), |_, id| id * id, Some(&progress));
+
+        assert_eq!(squares, (0..100).map(|id| id * id).collect::<Vec<_>>());
+        assert_eq!(calls.load(Ordering::Relaxed), 100);
+    }
+}
@@ -0,0 +1,448 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::error::Error;
+use std::fmt;
+
+use super::parallel::map_sources;
+use super::{Graph, NodeId, UNREACHABLE};
+
+/// How flight counts are turned into edge lengths for weighted shortest paths.
+///
+/// Busier routes get shorter lengths, so "closer" means better served.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeCost {
+    /// Every edge has length 1, i.e. plain hop count.
+    Hops,
+    /// `1 / flights`.
+    InverseFlights,
+    /// `-ln(flights / strength(from))`, where strength is the total flights
+    /// touching the departure node. Path lengths then multiply shares, like
+    /// the probability of a flight-weighted random walk taking the path.
+    NegLogShare,
+}
+
+impl EdgeCost {
+    pub(super) fn length(self, weight: u64, from_strength: u64) -> f64 {
+        match self {
+            EdgeCost::Hops => 1.0,
+            EdgeCost::InverseFlights => 1.0 / weight as f64,
+            EdgeCost::NegLogShare => -(weight as f64 / from_strength as f64).ln(),
+        }
+    }
+}
+
+/// A cost weighted harmonic centrality is not defined for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnsupportedCost(pub EdgeCost);
+
+impl fmt::Display for UnsupportedCost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            EdgeCost::NegLogShare => write!(
+                f,
+                "harmonic centrality is undefined for neg-log-share costs: an airport's \
+                 only route has length 0, so 1/d would be infinite"
+            ),
+            cost => write!(f, "harmonic centrality is undefined for {:?} costs", cost),
+        }
+    }
+}
+
+impl Error for UnsupportedCost {}
+
+/// Single-source shortest paths, indexed by `NodeId`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShortestPaths {
+    pub source: NodeId,
+    /// Path length to each node; `f64::INFINITY` if unreachable.
+    pub distances: Vec<f64>,
+    /// Previous node on the shortest path from `source`, or `None` for the
+    /// source itself and unreachable nodes.
+    pub predecessors: Vec<Option<NodeId>>,
+}
+
+impl ShortestPaths {
+    /// Nodes on the shortest path from `source` to `target`, both included.
+    pub fn path_to(&self, target: NodeId) -> Option<Vec<NodeId>> {
+        if !self.distances[target as usize].is_finite() {
+            return None;
+        }
+
+        let mut path = vec![target];
+        let mut current = target;
+        while let Some(previous) = self.predecessors[current as usize] {
+            path.push(previous);
+            current = previous;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Heap entry ordered so that `BinaryHeap` pops the smallest distance first,
+/// breaking ties by node ID for deterministic predecessor trees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct Candidate {
+    pub(super) distance: f64,
+    pub(super) node: NodeId,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .distance
+            .total_cmp(&self.distance)
+            .then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Graph {
+    /// Total flights on all edges touching each node, indexed by `NodeId`.
+    pub fn strengths(&self) -> Vec<u64> {
+        let csr = self.adjacency();
+        (0..self.node_count() as NodeId)
+            .map(|node| csr.weights(node).iter().copied().sum())
+            .collect()
+    }
+
+    /// Dijkstra from `source` with edge lengths derived from flight counts.
+    pub fn dijkstra(&self, source: NodeId, cost: EdgeCost) -> ShortestPaths {
+        self.dijkstra_with_strengths(source, cost, &self.strengths())
+    }
+
+    fn dijkstra_with_strengths(
+        &self,
+        source: NodeId,
+        cost: EdgeCost,
+        strengths: &[u64],
+    ) -> ShortestPaths {
+        let csr = self.adjacency();
+        let mut distances = vec![f64::INFINITY; self.node_count()];
+        let mut predecessors = vec![None; self.node_count()];
+        let mut heap = BinaryHeap::new();
+
+        distances[source as usize] = 0.0;
+        heap.push(Candidate {
+            distance: 0.0,
+            node: source,
+        });
+
+        while let Some(Candidate { distance, node }) = heap.pop() {
+            if distance > distances[node as usize] {
+                continue; // Stale entry, a shorter path was already found
+            }
+            let strength = strengths[node as usize];
+            for (&neighbor, &weight) in csr.neighbors(node).iter().zip(csr.weights(node)) {
+                let candidate = distance + cost.length(weight, strength);
+                if candidate < distances[neighbor as usize] {
+                    distances[neighbor as usize] = candidate;
+                    predecessors[neighbor as usize] = Some(node);
+                    heap.push(Candidate {
+                        distance: candidate,
+                        node: neighbor,
+                    });
+                }
+            }
+        }
+
+        ShortestPaths {
+            source,
+            distances,
+            predecessors,
+        }
+    }
+
+    /// Weighted path lengths from `start` to every reachable airport.
+    pub fn weighted_shortest_paths(&self, start: &str, cost: EdgeCost) -> HashMap<String, f64> {
+        let Some(start) = self.node_id(start) else {
+            return HashMap::new();
+        };
+
+        self.dijkstra(start, cost)
+            .distances
+            .into_iter()
+            .enumerate()
+            .filter(|(_, distance)| distance.is_finite())
+            .map(|(id, distance)| (self.node_code(id as NodeId).to_string(), distance))
+            .collect()
+    }
+
+    /// Harmonic centrality over weighted path lengths, indexed by `NodeId`.
+    ///
+    /// With `EdgeCost::Hops` this matches `harmonic_scores`.
+    ///
+    /// `NegLogShare` is rejected rather than supported: an airport's only
+    /// route costs 0 and a dominant one nearly 0, so `1 / d` is infinite or
+    /// huge and would rank spokes above the hubs they depend on. Clamping
+    /// lengths to some epsilon would only make the ranking depend on the
+    /// epsilon; use `InverseFlights` to have busy routes count as closer.
+    pub fn weighted_harmonic_scores(&self, cost: EdgeCost) -> Result<Vec<f64>, UnsupportedCost> {
+        if cost == EdgeCost::NegLogShare {
+            return Err(UnsupportedCost(cost));
+        }
+        let strengths = self.strengths();
+
+        let scores = map_sources(
+            self.node_count(),
+            || (),
+            |_, source| {
+                self.dijkstra_with_strengths(source, cost, &strengths)
+                    .distances
+                    .iter()
+                    .filter(|d| **d > 0.0 && d.is_finite())
+                    .map(|d| 1.0 / d)
+                    .sum()
+            },
+            None,
+        );
+        Ok(scores)
+    }
+
+    /// Weighted harmonic centrality of every airport, keyed by code.
+    pub fn weighted_harmonic_centrality(
+        &self,
+        cost: EdgeCost,
+    ) -> Result<HashMap<String, f64>, UnsupportedCost> {
+        Ok(self.scores_by_code(self.weighted_harmonic_scores(cost)?))
+    }
+}
+
+impl Graph {
+    /// Airports on a route from `from` to `to` with the fewest legs.
+    ///
+    /// Returns `None` if either airport is unknown or they are not connected.
+    pub fn shortest_path(&self, from: &str, to: &str) -> Option<Vec<String>> {
+        self.all_shortest_paths(from, to, 1).into_iter().next()
+    }
+
+    /// Up to `limit` distinct routes from `from` to `to` with the fewest legs,
+    /// sorted by the airport codes along the way.
+    pub fn all_shortest_paths(&self, from: &str, to: &str, limit: usize) -> Vec<Vec<String>> {
+        let (Some(source), Some(target)) = (self.node_id(from), self.node_id(to)) else {
+            return Vec::new();
+        };
+
+        let predecessors = self.bfs_predecessors(source);
+        if source != target && predecessors[target as usize].is_empty() {
+            return Vec::new(); // Not reachable
+        }
+
+        // Invert the predecessor lists restricted to nodes that lie on some
+        // shortest path to the target, so paths can be expanded forwards in
+        // airport-code order and `limit` keeps the first ones alphabetically.
+        let mut successors: Vec<Vec<NodeId>> = vec![Vec::new(); self.node_count()];
+        let mut on_path = vec![false; self.node_count()];
+        let mut pending = vec![target];
+        on_path[target as usize] = true;
+        while let Some(node) = pending.pop() {
+            for &previous in &predecessors[node as usize] {
+                successors[previous as usize].push(node);
+                if !on_path[previous as usize] {
+                    on_path[previous as usize] = true;
+                    pending.push(previous);
+                }
+            }
+        }
+        for next in &mut successors {
+            // Reverse order, since the stack below pops the last one first
+            next.sort_by(|&a, &b| self.node_code(b).cmp(self.node_code(a)));
+        }
+
+        let mut paths = Vec::new();
+        let mut stack = vec![vec![source]];
+        while let Some(partial) = stack.pop() {
+            if paths.len() >= limit {
+                break;
+            }
+            let last = *partial.last().unwrap();
+            if last == target {
+                paths.push(
+                    partial
+                        .iter()
+                        .map(|&id| self.node_code(id).to_string())
+                        .collect(),
+                );
+                continue;
+            }
+            for &next in &successors[last as usize] {
+                let mut extended = partial.clone();
+                extended.push(next);
+                stack.push(extended);
+            }
+        }
+
+        paths
+    }
+
+    /// Airports on the shortest weighted route from `from` to `to`.
+    pub fn weighted_path(&self, from: &str, to: &str, cost: EdgeCost) -> Option<Vec<String>> {
+        let source = self.node_id(from)?;
+        let target = self.node_id(to)?;
+        let path = self.dijkstra(source, cost).path_to(target)?;
+        Some(
+            path.iter()
+                .map(|&id| self.node_code(id).to_string())
+                .collect(),
+        )
+    }
+
+    /// Every predecessor of each node on some fewest-legs path from `source`.
+    fn bfs_predecessors(&self, source: NodeId) -> Vec<Vec<NodeId>> {
+        let csr = self.adjacency();
+        let mut distances = vec![UNREACHABLE; self.node_count()];
+        let mut predecessors = vec![Vec::new(); self.node_count()];
+        let mut queue = VecDeque::from([source]);
+        distances[source as usize] = 0;
+
+        while let Some(current) = queue.pop_front() {
+            let next = distances[current as usize] + 1;
+            for &neighbor in csr.neighbors(current) {
+                let known = distances[neighbor as usize];
+                if known == UNREACHABLE {
+                    distances[neighbor as usize] = next;
+                    queue.push_back(neighbor);
+                }
+                if distances[neighbor as usize] == next {
+                    predecessors[neighbor as usize].push(current);
+                }
+            }
+        }
+
+        predecessors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ranking::top_k;
+
+    fn triangle() -> Graph {
+        let mut graph = Graph::new();
+        graph.add_edge("A", "B", 100);
+        graph.add_edge("B", "C", 100);
+        graph.add_edge("A", "C", 2); // Direct but thinly served
+        graph
+    }
+
+    #[test]
+    fn test_dijkstra_prefers_busy_routes() {
+        let graph = triangle();
+        let a = graph.node_id("A").unwrap();
+        let b = graph.node_id("B").unwrap();
+        let c = graph.node_id("C").unwrap();
+
+        let weighted = graph.dijkstra(a, EdgeCost::InverseFlights);
+        assert!((weighted.distances[c as usize] - 0.02).abs() < 1e-12); // Via B
+        assert_eq!(weighted.predecessors[c as usize], Some(b));
+        assert_eq!(weighted.predecessors[a as usize], None);
+
+        let hops = graph.dijkstra(a, EdgeCost::Hops);
+        assert_eq!(hops.distances[c as usize], 1.0); // Direct edge
+        assert_eq!(hops.predecessors[c as usize], Some(a));
+    }
+
+    #[test]
+    fn test_neg_log_share_lengths() {
+        let graph = triangle();
+        let distances = graph.weighted_shortest_paths("A", EdgeCost::NegLogShare);
+
+        // A's strength is 102, so A-B costs -ln(100/102); B-C costs -ln(100/200)
+        let expected = -(100.0f64 / 102.0).ln() - (0.5f64).ln();
+        let direct = -(2.0f64 / 102.0).ln();
+        assert!(expected < direct);
+        assert!((distances["C"] - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_weighted_harmonic_matches_hop_harmonic() {
+        let mut graph = triangle();
+        graph.add_edge("C", "D", 1);
+        graph.add_edge("X", "Y", 1);
+
+        assert_eq!(
+            graph.weighted_harmonic_scores(EdgeCost::Hops),
+            Ok(graph.harmonic_scores())
+        );
+
+        let weighted = graph
+            .weighted_harmonic_centrality(EdgeCost::InverseFlights)
+            .unwrap();
+        assert!(weighted["B"] > weighted["D"]);
+        assert_eq!(weighted["X"], 1.0);
+    }
+
+    #[test]
+    fn test_weighted_harmonic_ranks_hub_first() {
+        let mut graph = Graph::new();
+        graph.add_edge("HNL", "LAX", 1000);
+        graph.add_edge("HNL", "MAJ", 50);
+        graph.add_edge("HNL", "NRT", 1);
+
+        let weighted = graph
+            .weighted_harmonic_centrality(EdgeCost::InverseFlights)
+            .unwrap();
+        let ranked: Vec<String> = top_k(&weighted, 4, |_| true)
+            .into_iter()
+            .map(|(code, _)| code)
+            .collect();
+        assert_eq!(ranked, ["HNL", "LAX", "MAJ", "NRT"]);
+
+        // Spokes with a single route would sit at distance 0 from their hub
+        assert_eq!(
+            graph.weighted_harmonic_scores(EdgeCost::NegLogShare),
+            Err(UnsupportedCost(EdgeCost::NegLogShare))
+        );
+    }
+
+    #[test]
+    fn test_shortest_path_itineraries() {
+        let mut graph = Graph::new();
+        graph.add_edge("BOS", "LHR", 1);
+        graph.add_edge("BOS", "KEF", 1);
+        graph.add_edge("LHR", "GUM", 1);
+        graph.add_edge("KEF", "GUM", 1);
+        graph.add_edge("GUM", "MAJ", 1);
+        graph.add_edge("ZZZ", "YYY", 1);
+
+        assert_eq!(
+            graph.shortest_path("BOS", "MAJ").unwrap(),
+            vec!["BOS", "KEF", "GUM", "MAJ"]
+        );
+        assert_eq!(
+            graph.all_shortest_paths("BOS", "MAJ", usize::MAX),
+            vec![
+                vec!["BOS", "KEF", "GUM", "MAJ"],
+                vec!["BOS", "LHR", "GUM", "MAJ"],
+            ]
+        );
+        assert_eq!(graph.all_shortest_paths("BOS", "MAJ", 1).len(), 1);
+        assert_eq!(graph.shortest_path("BOS", "BOS").unwrap(), vec!["BOS"]);
+        assert!(graph.shortest_path("BOS", "ZZZ").is_none());
+        assert!(graph.shortest_path("BOS", "NOPE").is_none());
+    }
+
+    #[test]
+    fn test_weighted_path() {
+        let graph = triangle();
+
+        assert_eq!(
+            graph
+                .weighted_path("A", "C", EdgeCost::InverseFlights)
+                .unwrap(),
+            vec!["A", "B", "C"]
+        );
+        assert_eq!(
+            graph.weighted_path("A", "C", EdgeCost::Hops).unwrap(),
+            vec!["A", "C"]
+        );
+    }
+}
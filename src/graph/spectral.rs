@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+
+use super::{Graph, NodeId};
+
+/// Result of an iterative centrality computation, indexed by `NodeId`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IterativeScores {
+    pub scores: Vec<f64>,
+    pub iterations: usize,
+    /// Whether the tolerance was met before `max_iterations` ran out.
+    pub converged: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageRankOptions {
+    /// Probability of following an edge rather than teleporting.
+    pub damping: f64,
+    /// Teleport weights by airport code; airports left out get none.
+    /// `None` teleports uniformly.
+    pub personalization: Option<HashMap<String, f64>>,
+    /// Stop once the L1 change between iterations drops below
+    /// `tolerance * node_count`.
+    pub tolerance: f64,
+    pub max_iterations: usize,
+}
+
+impl Default for PageRankOptions {
+    fn default() -> Self {
+        Self {
+            damping: 0.85,
+            personalization: None,
+            tolerance: 1e-6,
+            max_iterations: 100,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EigenvectorOptions {
+    /// Stop once the L1 change between iterations drops below
+    /// `tolerance * node_count`.
+    pub tolerance: f64,
+    pub max_iterations: usize,
+}
+
+impl Default for EigenvectorOptions {
+    fn default() -> Self {
+        Self {
+            tolerance: 1e-8,
+            max_iterations: 1000,
+        }
+    }
+}
+
+impl Graph {
+    /// PageRank with transitions proportional to flights on each edge.
+    ///
+    /// Scores sum to 1. Airports without flights teleport according to the
+    /// personalization vector.
+    pub fn pagerank_scores(&self, options: &PageRankOptions) -> IterativeScores {
+        let node_count = self.node_count();
+        if node_count == 0 {
+            return IterativeScores {
+                scores: Vec::new(),
+                iterations: 0,
+                converged: true,
+            };
+        }
+
+        let csr = self.adjacency();
+        let strengths = self.strengths();
+        let teleport = self.teleport_vector(options.personalization.as_ref());
+        let mut scores = teleport.clone();
+        let mut next = vec![0.0; node_count];
+
+        for iteration in 1..=options.max_iterations {
+            let dangling: f64 = (0..node_count)
+                .filter(|&node| strengths[node] == 0)
+                .map(|node| scores[node])
+                .sum();
+
+            for (value, &t) in next.iter_mut().zip(&teleport) {
+                *value = (1.0 - options.damping + options.damping * dangling) * t;
+            }
+            for node in 0..node_count as NodeId {
+                let strength = strengths[node as usize];
+                if strength == 0 {
+                    continue;
+                }
+                let share = options.damping * scores[node as usize] / strength as f64;
+                for (&neighbor, &weight) in csr.neighbors(node).iter().zip(csr.weights(node)) {
+                    next[neighbor as usize] += share * weight as f64;
+                }
+            }
+
+            let change: f64 = next.iter().zip(&scores).map(|(a, b)| (a - b).abs()).sum();
+            std::mem::swap(&mut scores, &mut next);
+            if change < options.tolerance * node_count as f64 {
+                return IterativeScores {
+                    scores,
+                    iterations: iteration,
+                    converged: true,
+                };
+            }
+        }
+
+        IterativeScores {
+            scores,
+            iterations: options.max_iterations,
+            converged: false,
+        }
+    }
+
+    /// PageRank of every airport, keyed by code.
+    pub fn pagerank(&self, options: &PageRankOptions) -> HashMap<String, f64> {
+        self.scores_by_code(self.pagerank_scores(options).scores)
+    }
+
+    /// Normalized teleport distribution, uniform unless personalized.
+    fn teleport_vector(&self, personalization: Option<&HashMap<String, f64>>) -> Vec<f64> {
+        let node_count = self.node_count();
+        let mut teleport = vec![0.0; node_count];
+        if let Some(weights) = personalization {
+            for (code, &weight) in weights {
+                if let Some(id) = self.node_id(code) {
+                    teleport[id as usize] = weight.max(0.0);
+                }
+            }
+        }
+
+        let total: f64 = teleport.iter().sum();
+        if total > 0.0 {
+            teleport.iter_mut().for_each(|t| *t /= total);
+        } else {
+            teleport.fill(1.0 / node_count as f64);
+        }
+        teleport
+    }
+
+    /// Eigenvector centrality of the flight-weighted adjacency matrix.
+    ///
+    /// Iterates `x <- (A + sI) x` with `s` the largest airport strength. No
+    /// eigenvalue of `A` exceeds `s` in magnitude, so the shift keeps power
+    /// iteration from oscillating on (near-)bipartite graphs like the
+    /// US/foreign network without changing the leading eigenvector, and it
+    /// scales with the flight counts so the spectral gap does not vanish on
+    /// busy routes. Scores have unit L2 norm.
+    pub fn eigenvector_scores(&self, options: &EigenvectorOptions) -> IterativeScores {
+        let node_count = self.node_count();
+        let csr = self.adjacency();
+        let shift = self.strengths().into_iter().max().unwrap_or(0).max(1) as f64;
+        let mut scores = vec![1.0 / node_count as f64; node_count];
+
+        for iteration in 1..=options.max_iterations {
+            let mut next: Vec<f64> = scores.iter().map(|x| x * shift).collect();
+            for node in 0..node_count as NodeId {
+                for (&neighbor, &weight) in csr.neighbors(node).iter().zip(csr.weights(node)) {
+                    next[neighbor as usize] += scores[node as usize] * weight as f64;
+                }
+            }
+
+            let norm = next.iter().map(|x| x * x).sum::<f64>().sqrt();
+            if norm == 0.0 {
+                break;
+            }
+            next.iter_mut().for_each(|x| *x /= norm);
+
+            let change: f64 = next.iter().zip(&scores).map(|(a, b)| (a - b).abs()).sum();
+            scores = next;
+            if change < options.tolerance * node_count as f64 {
+                return IterativeScores {
+                    scores,
+                    iterations: iteration,
+                    converged: true,
+                };
+            }
+        }
+
+        IterativeScores {
+            scores,
+            iterations: options.max_iterations,
+            converged: false,
+        }
+    }
+
+    /// Eigenvector centrality of every airport, keyed by code.
+    pub fn eigenvector_centrality(&self, options: &EigenvectorOptions) -> HashMap<String, f64> {
+        self.scores_by_code(self.eigenvector_scores(options).scores)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn star() -> Graph {
+        let mut graph = Graph::new();
+        graph.add_edge("HUB", "A", 10);
+        graph.add_edge("HUB", "B", 10);
+        graph.add_edge("HUB", "C", 1);
+        graph
+    }
+
+    #[test]
+    fn test_pagerank_weighted_star() {
+        let graph = star();
+        let result = graph.pagerank_scores(&PageRankOptions::default());
+        let scores = graph.pagerank(&PageRankOptions::default());
+
+        assert!(result.converged);
+        assert!((result.scores.iter().sum::<f64>() - 1.0).abs() < 1e-6);
+        assert!(scores["HUB"] > scores["A"]);
+        assert!(scores["A"] > scores["C"]); // Busier spoke ranks higher
+        assert!((scores["A"] - scores["B"]).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_pagerank_personalization() {
+        let graph = star();
+        let options = PageRankOptions {
+            personalization: Some(HashMap::from([("C".to_string(), 1.0)])),
+            ..PageRankOptions::default()
+        };
+        let personalized = graph.pagerank(&options);
+        let uniform = graph.pagerank(&PageRankOptions::default());
+
+        assert!(personalized["C"] > uniform["C"]);
+        assert!(personalized["A"] < uniform["A"]);
+    }
+
+    #[test]
+    fn test_eigenvector_converges_on_bipartite_graph() {
+        let graph = star();
+        let result = graph.eigenvector_scores(&EigenvectorOptions::default());
+        let scores = graph.eigenvector_centrality(&EigenvectorOptions::default());
+
+        assert!(result.converged);
+        assert!((result.scores.iter().map(|x| x * x).sum::<f64>() - 1.0).abs() < 1e-9);
+        // Leading eigenvector of a star: hub = 1/sqrt(2), spokes proportional to weight
+        assert!((scores["HUB"] - 0.5f64.sqrt()).abs() < 1e-6);
+        assert!((scores["A"] / scores["C"] - 10.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_eigenvector_converges_with_heavy_routes() {
+        let mut graph = Graph::new();
+        for (a, b, w) in [
+            ("JFK", "LHR", 120_000),
+            ("JFK", "CDG", 90_000),
+            ("LAX", "LHR", 60_000),
+            ("LAX", "NRT", 150_000),
+            ("MIA", "NRT", 30_000),
+        ] {
+            graph.add_edge(a, b, w);
+        }
+        let result = graph.eigenvector_scores(&EigenvectorOptions::default());
+
+        assert!(result.converged);
+        // Fixed point of the unshifted matrix: A x = lambda x
+        let csr = graph.adjacency();
+        let mut product = vec![0.0; graph.node_count()];
+        for node in 0..graph.node_count() as NodeId {
+            for (&neighbor, &weight) in csr.neighbors(node).iter().zip(csr.weights(node)) {
+                product[neighbor as usize] += result.scores[node as usize] * weight as f64;
+            }
+        }
+        let lambda: f64 = product.iter().zip(&result.scores).map(|(a, b)| a * b).sum();
+        for (a, b) in product.iter().zip(&result.scores) {
+            assert!((a - lambda * b).abs() < 1e-4 * lambda);
+        }
+    }
+}
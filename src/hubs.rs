@@ -0,0 +1,300 @@
+use std::collections::HashSet;
+
+use crate::data::{FlightData, ServiceClass};
+use crate::graph::{build_multiplex, Graph, LayerBy, NodeId};
+
+/// Thresholds for hub detection and network classification.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HubOptions {
+    /// Minimum fraction of the carrier's flights touching an airport for it
+    /// to count as a hub.
+    pub min_flight_share: f64,
+    /// Minimum number of routes from an airport for it to count as a hub.
+    pub min_hub_routes: usize,
+    /// Fraction of the flights that a network's core, its busiest airports,
+    /// must touch between them.
+    pub core_coverage: f64,
+    /// Networks whose core is at most this fraction of their airports are
+    /// hub-and-spoke.
+    pub max_core_fraction: f64,
+    /// Networks with fewer routes are too small to classify.
+    pub min_routes: usize,
+}
+
+impl Default for HubOptions {
+    fn default() -> Self {
+        Self {
+            min_flight_share: 0.1,
+            min_hub_routes: 3,
+            core_coverage: 0.8,
+            max_core_fraction: 0.2,
+            min_routes: 5,
+        }
+    }
+}
+
+/// Overall structure of a carrier's route network.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NetworkShape {
+    /// A small core of airports touches most flights.
+    HubAndSpoke,
+    /// Flights are spread over many airports.
+    PointToPoint,
+    /// Fewer routes than `HubOptions::min_routes`.
+    TooSmall,
+}
+
+/// An airport that a large part of a network's flights go through.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hub {
+    pub airport: String,
+    pub routes: usize,
+    pub flights: u64,
+    /// Fraction of the network's flights touching the airport.
+    pub flight_share: f64,
+}
+
+/// Hubs and concentration of one carrier's network.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkProfile {
+    pub name: String,
+    pub airports: usize,
+    pub routes: usize,
+    pub flights: u64,
+    /// Hubs by flights, busiest first.
+    pub hubs: Vec<Hub>,
+    /// Fraction of flights touching the busiest airport.
+    pub top1_share: f64,
+    /// Fraction of flights touching any of the three busiest airports.
+    pub top3_share: f64,
+    /// Fraction of flights touching any hub.
+    pub hub_share: f64,
+    /// Fraction of the airports, busiest first, needed to touch
+    /// `HubOptions::core_coverage` of the flights. The shape is classified on
+    /// this, so several mid-sized hubs count as much as one dominant one.
+    pub core_fraction: f64,
+    /// Freeman degree centralization: 1 for a star, 0 when every airport
+    /// has the same number of routes.
+    pub centralization: f64,
+    pub shape: NetworkShape,
+}
+
+/// Fraction of the flights on routes touching any of `airports`.
+fn share_touching(graph: &Graph, airports: &[NodeId], total: u64) -> f64 {
+    let airports: HashSet<NodeId> = airports.iter().copied().collect();
+    let csr = graph.adjacency();
+    let mut touching = 0u64;
+    for &from in &airports {
+        for (&to, &weight) in csr.neighbors(from).iter().zip(csr.weights(from)) {
+            // Count routes between two of the airports once
+            if !airports.contains(&to) || from < to {
+                touching += weight;
+            }
+        }
+    }
+    touching as f64 / total as f64
+}
+
+/// Number of airports from the front of `ranked` needed to touch `coverage`
+/// of the `total` flights, or all of them if they never do.
+fn core_size(graph: &Graph, ranked: &[NodeId], coverage: f64, total: u64) -> usize {
+    let csr = graph.adjacency();
+    let mut in_core = vec![false; graph.node_count()];
+    let mut touching = 0u64;
+    for (size, &from) in ranked.iter().enumerate() {
+        in_core[from as usize] = true;
+        for (&to, &weight) in csr.neighbors(from).iter().zip(csr.weights(from)) {
+            // Routes to airports already in the core were counted with them
+            if !in_core[to as usize] {
+                touching += weight;
+            }
+        }
+        if touching as f64 >= coverage * total as f64 {
+            return size + 1;
+        }
+    }
+    ranked.len()
+}
+
+fn centralization(degrees: &[usize]) -> f64 {
+    let n = degrees.len();
+    if n < 3 {
+        return 0.0;
+    }
+    let max = degrees.iter().copied().max().unwrap_or(0);
+    let spread: usize = degrees.iter().map(|&d| max - d).sum();
+    spread as f64 / ((n - 1) * (n - 2)) as f64
+}
+
+/// Finds the hubs of an undirected network and classifies its shape.
+pub fn profile_network(name: &str, graph: &Graph, options: &HubOptions) -> NetworkProfile {
+    let csr = graph.adjacency();
+    let strengths = graph.strengths();
+    let degrees: Vec<usize> = (0..graph.node_count() as NodeId)
+        .map(|node| csr.degree(node))
+        .collect();
+    let total: u64 = strengths.iter().sum::<u64>() / 2;
+
+    let mut ranked: Vec<NodeId> = (0..graph.node_count() as NodeId).collect();
+    ranked.sort_by(|&a, &b| {
+        strengths[b as usize]
+            .cmp(&strengths[a as usize])
+            .then_with(|| graph.node_code(a).cmp(graph.node_code(b)))
+    });
+
+    let flight_share = |node: NodeId| strengths[node as usize] as f64 / total as f64;
+    let hub_ids: Vec<NodeId> = ranked
+        .iter()
+        .copied()
+        .filter(|&node| {
+            flight_share(node) >= options.min_flight_share
+                && degrees[node as usize] >= options.min_hub_routes
+        })
+        .collect();
+    let hubs: Vec<Hub> = hub_ids
+        .iter()
+        .map(|&node| Hub {
+            airport: graph.node_code(node).to_string(),
+            routes: degrees[node as usize],
+            flights: strengths[node as usize],
+            flight_share: flight_share(node),
+        })
+        .collect();
+
+    let share_of = |airports: &[NodeId]| {
+        if total == 0 {
+            0.0
+        } else {
+            share_touching(graph, airports, total)
+        }
+    };
+    let top1_share = share_of(&ranked[..ranked.len().min(1)]);
+    let top3_share = share_of(&ranked[..ranked.len().min(3)]);
+    let hub_share = share_of(&hub_ids);
+    let core_fraction = if ranked.is_empty() {
+        0.0
+    } else {
+        core_size(graph, &ranked, options.core_coverage, total) as f64 / ranked.len() as f64
+    };
+
+    let shape = if graph.edge_count() < options.min_routes {
+        NetworkShape::TooSmall
+    } else if core_fraction <= options.max_core_fraction {
+        NetworkShape::HubAndSpoke
+    } else {
+        NetworkShape::PointToPoint
+    };
+
+    NetworkProfile {
+        name: name.to_string(),
+        airports: graph.node_count(),
+        routes: graph.edge_count(),
+        flights: total,
+        hubs,
+        top1_share,
+        top3_share,
+        hub_share,
+        core_fraction,
+        centralization: centralization(&degrees),
+        shape,
+    }
+}
+
+/// Profiles every carrier's network, busiest carrier first.
+pub fn carrier_profiles(
+    flights: &[FlightData],
+    class: ServiceClass,
+    options: &HubOptions,
+) -> Vec<NetworkProfile> {
+    let multiplex = build_multiplex(flights, class, LayerBy::Carrier);
+    let mut profiles: Vec<NetworkProfile> = multiplex
+        .layer_names()
+        .map(|carrier| profile_network(carrier, &multiplex.layer(carrier).unwrap(), options))
+        .collect();
+
+    profiles.sort_by(|a, b| b.flights.cmp(&a.flights).then_with(|| a.name.cmp(&b.name)));
+    profiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hub_and_spoke_carrier() {
+        let mut flights: Vec<FlightData> = ["LHR", "CDG", "FRA", "MAD", "GRU", "BOG"]
+            .into_iter()
+            .map(|foreign| FlightData::for_test(2019, 1, "MIA", foreign, "AA", 10, 0))
+            .collect();
+        flights.push(FlightData::for_test(2019, 1, "JFK", "LHR", "AA", 5, 0));
+        // Point-to-point: five unconnected routes
+        for (us, foreign) in [
+            ("BOS", "DUB"),
+            ("ORD", "SNN"),
+            ("SEA", "ORK"),
+            ("IAD", "KIR"),
+            ("EWR", "NOC"),
+        ] {
+            flights.push(FlightData::for_test(2019, 1, us, foreign, "EI", 10, 0));
+        }
+        flights.push(FlightData::for_test(2019, 1, "GUM", "MAJ", "PFQ", 3, 0));
+
+        let profiles = carrier_profiles(&flights, ServiceClass::Total, &HubOptions::default());
+        let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["AA", "EI", "PFQ"]);
+
+        let aa = &profiles[0];
+        assert_eq!((aa.airports, aa.routes, aa.flights), (8, 7, 65));
+        assert_eq!(aa.hubs.len(), 1);
+        assert_eq!((aa.hubs[0].airport.as_str(), aa.hubs[0].routes), ("MIA", 6));
+        assert!((aa.top1_share - 60.0 / 65.0).abs() < 1e-12);
+        assert_eq!((aa.top3_share, aa.hub_share), (1.0, 60.0 / 65.0));
+        assert_eq!(aa.core_fraction, 1.0 / 8.0); // MIA alone
+        assert_eq!(aa.shape, NetworkShape::HubAndSpoke);
+
+        let ei = &profiles[1];
+        assert!(ei.hubs.is_empty()); // Every airport has a single route
+        assert_eq!((ei.top3_share, ei.hub_share), (0.4, 0.0)); // BOS-DUB and EWR-NOC
+        assert_eq!(ei.centralization, 0.0);
+        assert_eq!(ei.core_fraction, 0.7); // Tied airports come in by code, DUB after BOS
+        assert_eq!(ei.shape, NetworkShape::PointToPoint);
+
+        assert_eq!(profiles[2].shape, NetworkShape::TooSmall);
+    }
+
+    #[test]
+    fn test_several_mid_sized_hubs() {
+        // One big hub and ten smaller ones, none with 10% of the flights
+        let mut flights: Vec<FlightData> = (0..10)
+            .map(|i| FlightData::for_test(2019, 1, "ATL", &format!("A{}", i), "DL", 40, 0))
+            .collect();
+        for hub in [
+            "JFK", "LAX", "DTW", "MSP", "SLC", "BOS", "SEA", "DFW", "ORD", "MIA",
+        ] {
+            for i in 0..5 {
+                let spoke = format!("{}{}", hub, i);
+                flights.push(FlightData::for_test(2019, 1, hub, &spoke, "DL", 10, 0));
+            }
+        }
+
+        let profiles = carrier_profiles(&flights, ServiceClass::Total, &HubOptions::default());
+        let dl = &profiles[0];
+        let hubs: Vec<&str> = dl.hubs.iter().map(|h| h.airport.as_str()).collect();
+        assert_eq!(hubs, ["ATL"]);
+        assert!(dl.hub_share < 0.5);
+        // ATL and seven of the smaller hubs touch 750 of the 900 flights
+        assert_eq!(dl.core_fraction, 8.0 / 71.0);
+        assert_eq!(dl.shape, NetworkShape::HubAndSpoke);
+    }
+
+    #[test]
+    fn test_star_centralization() {
+        let mut graph = Graph::new();
+        for spoke in ["B", "C", "D", "E"] {
+            graph.add_edge("A", spoke, 1);
+        }
+        let profile = profile_network("star", &graph, &HubOptions::default());
+        assert_eq!(profile.centralization, 1.0);
+        assert_eq!(profile.shape, NetworkShape::TooSmall);
+    }
+}
@@ -0,0 +1,13 @@
+//! Network analysis of the BTS T-100 international departures extract.
+
+pub mod carrier_groups;
+pub mod cli;
+pub mod compare;
+pub mod data;
+pub mod filter;
+pub mod graph;
+pub mod hubs;
+pub mod market;
+pub mod output;
+pub mod ranking;
+pub mod timeline;
@@ -0,0 +1,144 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::data::{FlightData, ServiceClass};
+
+/// One carrier's flights in a market and their fraction of the total.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CarrierShare {
+    pub carrier: String,
+    pub flights: u64,
+    pub share: f64,
+}
+
+/// Carrier shares of the flights at one airport or on one route.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Market {
+    pub total: u64,
+    /// Largest share first, ties broken by carrier code.
+    pub shares: Vec<CarrierShare>,
+}
+
+impl Market {
+    fn from_counts(counts: HashMap<String, u64>) -> Self {
+        let total: u64 = counts.values().sum();
+        let mut shares: Vec<CarrierShare> = counts
+            .into_iter()
+            .map(|(carrier, flights)| CarrierShare {
+                carrier,
+                flights,
+                share: flights as f64 / total as f64,
+            })
+            .collect();
+        shares.sort_by(|a, b| {
+            b.flights
+                .cmp(&a.flights)
+                .then_with(|| a.carrier.cmp(&b.carrier))
+        });
+        Self { total, shares }
+    }
+
+    /// Carrier with the most flights.
+    pub fn leader(&self) -> &CarrierShare {
+        &self.shares[0]
+    }
+
+    /// Herfindahl–Hirschman index on the usual 0–10,000 scale (shares in
+    /// percent, squared and summed). 10,000 means a single carrier.
+    pub fn hhi(&self) -> f64 {
+        self.shares.iter().map(|s| (s.share * 100.0).powi(2)).sum()
+    }
+
+    /// Number of equal-sized carriers that would give the same HHI.
+    pub fn effective_competitors(&self) -> f64 {
+        10_000.0 / self.hhi()
+    }
+}
+
+/// Splits the `class` flights of each market (as named by `keys`) by carrier.
+/// Markets without any flights of that class are left out.
+pub fn markets<K, I, F>(flights: &[FlightData], class: ServiceClass, keys: F) -> BTreeMap<K, Market>
+where
+    K: Ord,
+    I: IntoIterator<Item = K>,
+    F: Fn(&FlightData) -> I,
+{
+    let mut counts: BTreeMap<K, HashMap<String, u64>> = BTreeMap::new();
+
+    for flight in flights {
+        let count = flight.flights(class);
+        if count == 0 {
+            continue;
+        }
+        for key in keys(flight) {
+            *counts
+                .entry(key)
+                .or_default()
+                .entry(flight.carrier.clone())
+                .or_insert(0) += count as u64;
+        }
+    }
+
+    counts
+        .into_iter()
+        .map(|(key, counts)| (key, Market::from_counts(counts)))
+        .collect()
+}
+
+/// Carrier shares at each airport, counting flights at either end of a route.
+pub fn airport_markets(flights: &[FlightData], class: ServiceClass) -> BTreeMap<String, Market> {
+    markets(flights, class, |flight| {
+        [flight.us_airport.clone(), flight.foreign_airport.clone()]
+    })
+}
+
+/// Carrier shares of the departures from each US gateway only.
+pub fn gateway_markets(flights: &[FlightData], class: ServiceClass) -> BTreeMap<String, Market> {
+    markets(flights, class, |flight| [flight.us_airport.clone()])
+}
+
+/// Carrier shares on each (US gateway, foreign airport) route.
+pub fn route_markets(
+    flights: &[FlightData],
+    class: ServiceClass,
+) -> BTreeMap<(String, String), Market> {
+    markets(flights, class, |flight| {
+        [(flight.us_airport.clone(), flight.foreign_airport.clone())]
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_market_shares_and_hhi() {
+        let flights = vec![
+            FlightData::for_test(2019, 1, "JFK", "LHR", "AA", 60, 0),
+            FlightData::for_test(2019, 2, "JFK", "LHR", "BA", 30, 10),
+            FlightData::for_test(2019, 1, "JFK", "CDG", "AF", 100, 0),
+            FlightData::for_test(2019, 1, "GUM", "MAJ", "UA", 7, 0),
+            FlightData::for_test(2019, 1, "GUM", "MAJ", "PFQ", 0, 3),
+        ];
+
+        let airports = airport_markets(&flights, ServiceClass::Total);
+        let jfk = &airports["JFK"];
+        assert_eq!(jfk.total, 200);
+        assert_eq!(jfk.leader().carrier, "AF");
+        assert_eq!(jfk.shares[1].carrier, "AA");
+        assert!(!gateway_markets(&flights, ServiceClass::Total).contains_key("LHR"));
+        assert!((jfk.hhi() - (50f64.powi(2) + 30f64.powi(2) + 20f64.powi(2))).abs() < 1e-9);
+
+        let routes = route_markets(&flights, ServiceClass::Total);
+        let lhr = &routes[&("JFK".to_string(), "LHR".to_string())];
+        assert_eq!(
+            (lhr.leader().carrier.as_str(), lhr.leader().share),
+            ("AA", 0.6)
+        );
+        assert!((lhr.effective_competitors() - 10_000.0 / 5_200.0).abs() < 1e-9);
+
+        let scheduled = route_markets(&flights, ServiceClass::Scheduled);
+        let maj = &scheduled[&("GUM".to_string(), "MAJ".to_string())];
+        assert_eq!(maj.shares.len(), 1); // PFQ flew charters only
+        assert_eq!((maj.hhi(), maj.effective_competitors()), (10_000.0, 1.0));
+    }
+}
@@ -0,0 +1,342 @@
+use std::error::Error;
+use std::fmt;
+use std::io::Write;
+use std::str::FromStr;
+
+/// How command results are written out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Aligned columns for reading in a terminal.
+    #[default]
+    Text,
+    /// Commands with several tables put each under a row holding its
+    /// title, separated by blank lines.
+    Csv,
+    /// An array with one object per row; commands with several tables write
+    /// one array of `{"title", "rows"}` objects instead.
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "csv" => Ok(OutputFormat::Csv),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!("unknown output format '{}'", s)),
+        }
+    }
+}
+
+/// One cell of a result table.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Text(String),
+    Int(i64),
+    Float(f64),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Text(text) => write!(f, "{}", text),
+            Value::Int(n) => write!(f, "{}", n),
+            Value::Float(x) => write!(f, "{:.4}", x),
+        }
+    }
+}
+
+impl From<&str> for Value {
+    fn from(text: &str) -> Self {
+        Value::Text(text.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(text: String) -> Self {
+        Value::Text(text)
+    }
+}
+
+impl From<u32> for Value {
+    fn from(n: u32) -> Self {
+        Value::Int(n as i64)
+    }
+}
+
+impl From<u64> for Value {
+    fn from(n: u64) -> Self {
+        Value::Int(n as i64)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(n: i64) -> Self {
+        Value::Int(n)
+    }
+}
+
+impl From<usize> for Value {
+    fn from(n: usize) -> Self {
+        Value::Int(n as i64)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(x: f64) -> Self {
+        Value::Float(x)
+    }
+}
+
+/// A titled result table that every command renders through.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub title: String,
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    pub fn new(title: &str, headers: &[&str]) -> Self {
+        Self {
+            title: title.to_string(),
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, row: Vec<Value>) {
+        self.rows.push(row);
+    }
+
+    pub fn write(&self, out: &mut dyn Write, format: OutputFormat) -> Result<(), Box<dyn Error>> {
+        match format {
+            OutputFormat::Text => self.write_text(out)?,
+            OutputFormat::Csv => self.write_csv(out)?,
+            OutputFormat::Json => self.write_json(out)?,
+        }
+        Ok(())
+    }
+
+    fn write_text(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        let cells: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|v| v.to_string()).collect())
+            .collect();
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.len()).collect();
+        for row in &cells {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+
+        writeln!(out, "{}", self.title)?;
+        let line = |row: &[String]| {
+            row.iter()
+                .zip(&widths)
+                .map(|(cell, &width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+        writeln!(out, "{}", line(&self.headers))?;
+        for row in &cells {
+            writeln!(out, "{}", line(row))?;
+        }
+        Ok(())
+    }
+
+    fn write_csv(&self, out: &mut dyn Write) -> Result<(), csv::Error> {
+        let mut writer = csv::Writer::from_writer(out);
+        writer.write_record(&self.headers)?;
+        for row in &self.rows {
+            writer.write_record(row.iter().map(|value| match value {
+                Value::Float(x) => x.to_string(), // Full precision for machine use
+                other => other.to_string(),
+            }))?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn write_json(&self, out: &mut dyn Write) -> std::io::Result<()> {
+        writeln!(out, "{}", self.json_rows(""))
+    }
+
+    /// The rows as a JSON array of objects, with each line after the first
+    /// prefixed by `indent`.
+    fn json_rows(&self, indent: &str) -> String {
+        let rows: Vec<String> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let fields: Vec<String> = self
+                    .headers
+                    .iter()
+                    .zip(row)
+                    .map(|(header, value)| {
+                        let value = match value {
+                            Value::Text(text) => json_string(text),
+                            Value::Int(n) => n.to_string(),
+                            Value::Float(x) if x.is_finite() => x.to_string(),
+                            Value::Float(_) => "null".to_string(),
+                        };
+                        format!("{}: {}", json_string(header), value)
+                    })
+                    .collect();
+                format!("{}  {{{}}}", indent, fields.join(", "))
+            })
+            .collect();
+
+        if rows.is_empty() {
+            "[]".to_string()
+        } else {
+            format!("[\n{}\n{}]", rows.join(",\n"), indent)
+        }
+    }
+}
+
+/// Writes a command's tables as one document in `format`.
+///
+/// A single table is written as is; several are separated by blank lines in
+/// text, labelled with a title row in CSV and wrapped in one array in JSON.
+pub fn write_tables(
+    tables: &[Table],
+    out: &mut dyn Write,
+    format: OutputFormat,
+) -> Result<(), Box<dyn Error>> {
+    if let [table] = tables {
+        return table.write(out, format);
+    }
+
+    match format {
+        OutputFormat::Text => {
+            for (i, table) in tables.iter().enumerate() {
+                if i > 0 {
+                    writeln!(out)?;
+                }
+                table.write_text(out)?;
+            }
+        }
+        OutputFormat::Csv => {
+            for (i, table) in tables.iter().enumerate() {
+                if i > 0 {
+                    writeln!(out)?;
+                }
+                {
+                    let mut writer = csv::Writer::from_writer(&mut *out);
+                    writer.write_record([&table.title])?;
+                    writer.flush()?;
+                }
+                table.write_csv(out)?;
+            }
+        }
+        OutputFormat::Json => {
+            let entries: Vec<String> = tables
+                .iter()
+                .map(|table| {
+                    format!(
+                        "  {{\"title\": {}, \"rows\": {}}}",
+                        json_string(&table.title),
+                        table.json_rows("  ")
+                    )
+                })
+                .collect();
+            if entries.is_empty() {
+                writeln!(out, "[]")?;
+            } else {
+                writeln!(out, "[\n{}\n]", entries.join(",\n"))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Table {
+        let mut table = Table::new("Top Airports", &["airport", "flights", "share"]);
+        table.push(vec!["JFK".into(), 120u32.into(), 0.75.into()]);
+        table.push(vec!["A\"B".into(), 4u32.into(), 0.025.into()]);
+        table
+    }
+
+    fn render(format: OutputFormat) -> String {
+        let mut out = Vec::new();
+        sample().write(&mut out, format).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_table_formats() {
+        assert_eq!(
+            render(OutputFormat::Text),
+            "Top Airports\nairport  flights  share\nJFK      120      0.7500\nA\"B      4        0.0250\n"
+        );
+        assert_eq!(
+            render(OutputFormat::Csv),
+            "airport,flights,share\nJFK,120,0.75\n\"A\"\"B\",4,0.025\n"
+        );
+        assert_eq!(
+            render(OutputFormat::Json),
+            "[\n  {\"airport\": \"JFK\", \"flights\": 120, \"share\": 0.75},\n  {\"airport\": \"A\\\"B\", \"flights\": 4, \"share\": 0.025}\n]\n"
+        );
+    }
+
+    #[test]
+    fn test_write_tables_as_one_document() {
+        let mut empty = Table::new("Empty, Too", &["airport"]);
+        empty.title.push_str(" \"Quoted\"");
+        let tables = [sample(), empty];
+        let write = |format| {
+            let mut out = Vec::new();
+            write_tables(&tables, &mut out, format).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        assert_eq!(
+            write(OutputFormat::Csv),
+            "Top Airports\nairport,flights,share\nJFK,120,0.75\n\"A\"\"B\",4,0.025\n\n\
+             \"Empty, Too \"\"Quoted\"\"\"\nairport\n"
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&write(OutputFormat::Json)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {"title": "Top Airports", "rows": [
+                    {"airport": "JFK", "flights": 120, "share": 0.75},
+                    {"airport": "A\"B", "flights": 4, "share": 0.025},
+                ]},
+                {"title": "Empty, Too \"Quoted\"", "rows": []},
+            ])
+        );
+
+        // A lone table keeps its plain form
+        let mut out = Vec::new();
+        write_tables(&tables[..1], &mut out, OutputFormat::Json).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), render(OutputFormat::Json));
+    }
+}
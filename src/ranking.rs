@@ -0,0 +1,54 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// The `k` highest-scoring entries that pass `filter`, best first.
+///
+/// Equal scores are ordered by airport code, so rankings are reproducible
+/// regardless of `HashMap` iteration order. Pass `usize::MAX` for a full
+/// ranking.
+pub fn top_k<T, F>(scores: &HashMap<String, T>, k: usize, filter: F) -> Vec<(String, T)>
+where
+    T: PartialOrd + Copy,
+    F: Fn(&str) -> bool,
+{
+    let mut ranked: Vec<_> = scores
+        .iter()
+        .filter(|(code, _)| filter(code))
+        .map(|(code, &score)| (code.clone(), score))
+        .collect();
+
+    ranked.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| a.0.cmp(&b.0))
+    });
+    ranked.truncate(k);
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_top_k_ties_and_filter() {
+        let scores: HashMap<String, f64> = [("C", 2.0), ("B", 2.0), ("A", 1.0), ("D", 3.0)]
+            .into_iter()
+            .map(|(code, score)| (code.to_string(), score))
+            .collect();
+
+        let top = top_k(&scores, 3, |_| true);
+        assert_eq!(
+            top,
+            vec![
+                ("D".to_string(), 3.0),
+                ("B".to_string(), 2.0), // Tie with C broken by code
+                ("C".to_string(), 2.0),
+            ]
+        );
+
+        let filtered = top_k(&scores, 5, |code| code != "D");
+        assert_eq!(filtered.len(), 3);
+        assert_eq!(filtered[0].0, "B");
+    }
+}
@@ -0,0 +1,497 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::data::{FlightData, ServiceClass, YearMonth};
+use crate::graph::{Breakdowns, Graph};
+use crate::ranking::top_k;
+
+/// Length of the periods a snapshot series is cut into.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Granularity {
+    #[default]
+    Year,
+    Month,
+    /// Consecutive windows of this many months, counted from January of the
+    /// first year in the data (so `Months(3)` gives calendar quarters).
+    Months(u32),
+}
+
+impl Granularity {
+    fn months(self) -> u32 {
+        match self {
+            Granularity::Year => 12,
+            Granularity::Month => 1,
+            Granularity::Months(n) => n.max(1),
+        }
+    }
+}
+
+/// An inclusive span of calendar months covered by one snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Period {
+    pub start: YearMonth,
+    pub end: YearMonth,
+}
+
+impl Period {
+    pub fn contains(&self, date: YearMonth) -> bool {
+        (self.start..=self.end).contains(&date)
+    }
+}
+
+/// Shown as `2001` for a calendar year, `2001-09` for a single month and
+/// `2001-01..2001-03` otherwise.
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.start == self.end {
+            write!(f, "{}", self.start)
+        } else if self.start.year == self.end.year && self.start.month == 1 && self.end.month == 12
+        {
+            write!(f, "{:04}", self.start.year)
+        } else {
+            write!(f, "{}..{}", self.start, self.end)
+        }
+    }
+}
+
+impl FromStr for Period {
+    type Err = ();
+
+    /// Parses the `Display` forms: `2001`, `2001-09` or `2001-01..2001-03`.
+    /// The ends of a range may themselves be years or months.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some((start, end)) = s.split_once("..") {
+            let (start, end): (Period, Period) = (start.parse()?, end.parse()?);
+            return if start.start <= end.end {
+                Ok(Period {
+                    start: start.start,
+                    end: end.end,
+                })
+            } else {
+                Err(())
+            };
+        }
+        if s.len() == 4 {
+            let year = s.parse().map_err(|_| ())?;
+            return Ok(Period {
+                start: YearMonth { year, month: 1 },
+                end: YearMonth { year, month: 12 },
+            });
+        }
+        let month: YearMonth = s.parse()?;
+        Ok(Period {
+            start: month,
+            end: month,
+        })
+    }
+}
+
+/// Months since year 0, so consecutive months differ by one.
+fn month_index(date: YearMonth) -> u32 {
+    date.year * 12 + date.month - 1
+}
+
+fn from_month_index(index: u32) -> YearMonth {
+    YearMonth {
+        year: index / 12,
+        month: index % 12 + 1,
+    }
+}
+
+/// How snapshot graphs are cut and built.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SnapshotOptions {
+    pub granularity: Granularity,
+    pub class: ServiceClass,
+    pub directed: bool,
+}
+
+/// The graph of the flights reported within one period.
+#[derive(Debug)]
+pub struct Snapshot {
+    pub period: Period,
+    /// Records dated within the period, whether or not they had flights of
+    /// the chosen class.
+    pub records: usize,
+    pub graph: Graph,
+}
+
+/// The consecutive periods of one granularity covering a set of records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeriodGrid {
+    anchor: u32, // Month index of the first period's start
+    width: u32,
+    count: u32,
+}
+
+impl PeriodGrid {
+    /// Periods from the one holding the earliest record to the one holding
+    /// the latest, or `None` without records.
+    pub fn covering(flights: &[FlightData], granularity: Granularity) -> Option<Self> {
+        let first = flights.iter().map(|f| f.date).min()?;
+        let last = flights.iter().map(|f| f.date).max()?;
+
+        let width = granularity.months();
+        let anchor = match granularity {
+            Granularity::Month => month_index(first),
+            _ => first.year * 12,
+        };
+        Some(Self {
+            anchor,
+            width,
+            count: (month_index(last) - anchor) / width + 1,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.count as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    /// The periods in chronological order.
+    pub fn periods(&self) -> impl Iterator<Item = Period> + '_ {
+        (0..self.count).map(|i| {
+            let start = self.anchor + i * self.width;
+            Period {
+                start: from_month_index(start),
+                end: from_month_index(start + self.width - 1),
+            }
+        })
+    }
+
+    /// Position of the period containing `date`, if it is on the grid.
+    pub fn index_of(&self, date: YearMonth) -> Option<usize> {
+        let index = month_index(date).checked_sub(self.anchor)? / self.width;
+        (index < self.count).then_some(index as usize)
+    }
+}
+
+/// Builds one graph per period, from the earliest to the latest month in
+/// `flights`.
+///
+/// Periods without any records are kept as empty graphs so the series has
+/// no gaps.
+pub fn snapshots(flights: &[FlightData], options: &SnapshotOptions) -> Vec<Snapshot> {
+    let Some(grid) = PeriodGrid::covering(flights, options.granularity) else {
+        return Vec::new();
+    };
+
+    let mut snapshots: Vec<Snapshot> = grid
+        .periods()
+        .map(|period| Snapshot {
+            period,
+            records: 0,
+            graph: if options.directed {
+                Graph::new_directed()
+            } else {
+                Graph::new()
+            },
+        })
+        .collect();
+
+    for flight in flights {
+        let snapshot = &mut snapshots[grid.index_of(flight.date).unwrap()];
+        snapshot.records += 1;
+        snapshot
+            .graph
+            .add_flight(flight, options.class, Breakdowns::default());
+    }
+
+    snapshots
+}
+
+/// Summary metrics of one snapshot graph.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeriodMetrics {
+    pub period: Period,
+    pub records: usize,
+    pub nodes: usize,
+    pub edges: usize,
+    /// Total edge weight, i.e. flights of the chosen class.
+    pub flights: u64,
+    pub components: usize,
+    pub largest_component: usize,
+    /// Highest scoring airports, best first.
+    pub top: Vec<(String, f64)>,
+}
+
+impl Snapshot {
+    /// Computes the snapshot's metrics, ranking airports with `score`.
+    pub fn metrics<F>(&self, top: usize, score: F) -> PeriodMetrics
+    where
+        F: Fn(&Graph) -> HashMap<String, f64>,
+    {
+        let graph = &self.graph;
+        let components = graph.component_ids();
+        let flights = graph.routes().iter().map(|(_, w)| *w).sum();
+        let top = if graph.node_count() == 0 || top == 0 {
+            Vec::new()
+        } else {
+            top_k(&score(graph), top, |_| true)
+        };
+
+        PeriodMetrics {
+            period: self.period,
+            records: self.records,
+            nodes: graph.node_count(),
+            edges: graph.edge_count(),
+            flights,
+            components: components.len(),
+            largest_component: components.iter().map(Vec::len).max().unwrap_or(0),
+            top,
+        }
+    }
+}
+
+/// Metrics of every period's snapshot, in chronological order.
+pub fn metric_series<F>(
+    flights: &[FlightData],
+    options: &SnapshotOptions,
+    top: usize,
+    score: F,
+) -> Vec<PeriodMetrics>
+where
+    F: Fn(&Graph) -> HashMap<String, f64>,
+{
+    snapshots(flights, options)
+        .iter()
+        .map(|snapshot| snapshot.metrics(top, &score))
+        .collect()
+}
+
+/// Monthly flight totals per key over a fixed span of months.
+///
+/// Every series has one value per month of `span`, with zeros for months in
+/// which the key saw no flights.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonthlySeries<K: Ord> {
+    pub span: Period,
+    pub series: BTreeMap<K, Vec<u32>>,
+}
+
+impl<K: Ord> MonthlySeries<K> {
+    /// Number of months in the span, i.e. the length of every series.
+    pub fn month_count(&self) -> usize {
+        (month_index(self.span.end) - month_index(self.span.start) + 1) as usize
+    }
+
+    /// The months of the span, in order, matching the positions in each series.
+    pub fn months(&self) -> impl Iterator<Item = YearMonth> {
+        (month_index(self.span.start)..=month_index(self.span.end)).map(from_month_index)
+    }
+
+    pub fn get(&self, key: &K) -> Option<&[u32]> {
+        self.series.get(key).map(Vec::as_slice)
+    }
+
+    /// Flights for `key` in one month, zero outside the span or for unknown keys.
+    pub fn value(&self, key: &K, month: YearMonth) -> u32 {
+        if !self.span.contains(month) {
+            return 0;
+        }
+        let offset = (month_index(month) - month_index(self.span.start)) as usize;
+        self.get(key).map_or(0, |values| values[offset])
+    }
+}
+
+/// Monthly totals of `class` flights, with each record counted under every
+/// key returned by `keys`.
+///
+/// The span runs from the earliest to the latest month in `flights`, so
+/// series built from the same records line up month for month. Without any
+/// records there are no series and the span is a placeholder month.
+pub fn monthly_series<K, I, F>(
+    flights: &[FlightData],
+    class: ServiceClass,
+    keys: F,
+) -> MonthlySeries<K>
+where
+    K: Ord,
+    I: IntoIterator<Item = K>,
+    F: Fn(&FlightData) -> I,
+{
+    let (first, last) = match (
+        flights.iter().map(|f| f.date).min(),
+        flights.iter().map(|f| f.date).max(),
+    ) {
+        (Some(first), Some(last)) => (first, last),
+        _ => (
+            YearMonth { year: 0, month: 1 },
+            YearMonth { year: 0, month: 1 },
+        ),
+    };
+    let mut result = MonthlySeries {
+        span: Period {
+            start: first,
+            end: last,
+        },
+        series: BTreeMap::new(),
+    };
+    let len = result.month_count();
+
+    for flight in flights {
+        let count = flight.flights(class);
+        if count == 0 {
+            continue;
+        }
+        let offset = (month_index(flight.date) - month_index(first)) as usize;
+        for key in keys(flight) {
+            result.series.entry(key).or_insert_with(|| vec![0; len])[offset] += count;
+        }
+    }
+
+    result
+}
+
+/// Monthly flights at each airport, counting both the US gateway and the
+/// foreign end of every route.
+pub fn airport_series(flights: &[FlightData], class: ServiceClass) -> MonthlySeries<String> {
+    monthly_series(flights, class, |flight| {
+        [flight.us_airport.clone(), flight.foreign_airport.clone()]
+    })
+}
+
+/// Monthly flights on each (US gateway, foreign airport) route.
+pub fn route_series(
+    flights: &[FlightData],
+    class: ServiceClass,
+) -> MonthlySeries<(String, String)> {
+    monthly_series(flights, class, |flight| {
+        [(flight.us_airport.clone(), flight.foreign_airport.clone())]
+    })
+}
+
+/// Monthly flights operated by each carrier.
+pub fn carrier_series(flights: &[FlightData], class: ServiceClass) -> MonthlySeries<String> {
+    monthly_series(flights, class, |flight| [flight.carrier.clone()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::DistanceCentrality;
+
+    fn sample() -> Vec<FlightData> {
+        vec![
+            FlightData::for_test(2000, 12, "JFK", "LHR", "AA", 10, 0),
+            FlightData::for_test(2001, 3, "JFK", "LHR", "AA", 8, 0),
+            FlightData::for_test(2001, 3, "JFK", "CDG", "AF", 4, 0),
+            FlightData::for_test(2001, 11, "MIA", "CDG", "AF", 2, 1),
+            FlightData::for_test(2003, 1, "MIA", "GRU", "AA", 0, 6),
+        ]
+    }
+
+    #[test]
+    fn test_yearly_snapshots() {
+        let flights = sample();
+        let snapshots = snapshots(&flights, &SnapshotOptions::default());
+
+        let periods: Vec<String> = snapshots.iter().map(|s| s.period.to_string()).collect();
+        assert_eq!(periods, ["2000", "2001", "2002", "2003"]);
+        assert_eq!(snapshots[1].records, 3);
+        assert_eq!(snapshots[1].graph.node_count(), 4);
+        assert_eq!(snapshots[1].graph.edge_weight("JFK", "LHR"), Some(8));
+        assert_eq!(snapshots[2].graph.node_count(), 0); // Gap year kept
+    }
+
+    #[test]
+    fn test_month_and_window_periods() {
+        let flights = sample();
+        let monthly = snapshots(
+            &flights,
+            &SnapshotOptions {
+                granularity: Granularity::Month,
+                ..SnapshotOptions::default()
+            },
+        );
+        assert_eq!(monthly.len(), 26); // 2000-12 through 2003-01
+        assert_eq!(monthly[3].period.to_string(), "2001-03");
+        assert_eq!(monthly[3].graph.edge_count(), 2);
+
+        let quarterly = snapshots(
+            &flights,
+            &SnapshotOptions {
+                granularity: Granularity::Months(3),
+                class: ServiceClass::Charter,
+                ..SnapshotOptions::default()
+            },
+        );
+        assert_eq!(quarterly[0].period.to_string(), "2000-01..2000-03");
+        assert_eq!(quarterly.len(), 13);
+        assert_eq!(quarterly[7].period.to_string(), "2001-10..2001-12");
+        assert_eq!(quarterly[7].graph.edge_weight("CDG", "MIA"), Some(1));
+        assert!(quarterly[4]
+            .period
+            .contains(YearMonth::new(2001, 3).unwrap()));
+    }
+
+    #[test]
+    fn test_parse_period() {
+        for (text, shown) in [
+            ("2001", "2001"),
+            ("2001-09", "2001-09"),
+            ("2001-01..2001-03", "2001-01..2001-03"),
+            ("1999..2001", "1999-01..2001-12"),
+        ] {
+            assert_eq!(text.parse::<Period>().unwrap().to_string(), shown);
+        }
+        assert!("2001-03..2001-01".parse::<Period>().is_err());
+        assert!("2001-13".parse::<Period>().is_err());
+        assert!("spring".parse::<Period>().is_err());
+    }
+
+    #[test]
+    fn test_monthly_series_zero_filled() {
+        let flights = sample();
+
+        let airports = airport_series(&flights, ServiceClass::Total);
+        assert_eq!(airports.month_count(), 26);
+        assert_eq!(airports.months().nth(3), YearMonth::new(2001, 3));
+        assert_eq!(airports.months().last(), YearMonth::new(2003, 1));
+        let jfk = airports.get(&"JFK".to_string()).unwrap();
+        assert_eq!((jfk[0], jfk[1], jfk[3]), (10, 0, 12));
+        assert_eq!(jfk.iter().sum::<u32>(), 22);
+        assert_eq!(
+            airports.value(&"CDG".to_string(), YearMonth::new(2001, 11).unwrap()),
+            3
+        );
+        assert_eq!(
+            airports.value(&"CDG".to_string(), YearMonth::new(1999, 1).unwrap()),
+            0
+        );
+
+        let routes = route_series(&flights, ServiceClass::Charter);
+        assert_eq!(routes.series.len(), 2); // Only routes with charter flights
+        let key = ("MIA".to_string(), "GRU".to_string());
+        assert_eq!(routes.get(&key).unwrap().last(), Some(&6));
+
+        let carriers = carrier_series(&flights, ServiceClass::Scheduled);
+        let aa = carriers.get(&"AA".to_string()).unwrap();
+        assert_eq!((aa[0], aa[3], aa[25]), (10, 8, 0));
+        assert!(
+            monthly_series(&[], ServiceClass::Total, |f| [f.carrier.clone()])
+                .series
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_metric_series() {
+        let flights = sample();
+        let series = metric_series(&flights, &SnapshotOptions::default(), 1, |graph| {
+            graph.centrality(DistanceCentrality::Harmonic)
+        });
+
+        let busy = &series[1];
+        assert_eq!((busy.nodes, busy.edges, busy.flights), (4, 3, 15));
+        assert_eq!((busy.components, busy.largest_component), (1, 4));
+        assert_eq!(busy.top[0].0, "CDG");
+        assert!(series[2].top.is_empty());
+        assert_eq!(series[2].components, 0);
+    }
+}